@@ -70,7 +70,9 @@ impl App {
         self.egui.run(|ctx| self.ui.update(ctx));
         self.canvas.set_draw_color(to_sdl_color(self.ui.color));
         self.canvas.clear();
-        self.egui.paint(&mut self.canvas);
+        if let Err(e) = self.egui.paint(&mut self.canvas) {
+            eprintln!("egui: {e}");
+        }
         self.canvas.present();
     }
 }
@@ -19,6 +19,7 @@
 //! 4. Paint egui output over whatever you drew via [`EguiCanvas::paint`]
 //!
 pub mod painter;
+mod tiles;
 pub use painter::*;
 
 use sdl2::render::{Canvas, RenderTarget};
@@ -126,18 +127,91 @@ impl<C> EguiCanvas<C> {
 
     /// Paint the results of the last call to [`Self::run`]. Clear the canvas (and
     /// draw your own content) beforehand; present it afterwards.
-    pub fn paint<T: RenderTarget<Context = C>>(&mut self, canvas: &mut Canvas<T>) {
+    ///
+    /// An error names a texture the renderer refused. The frame is painted all
+    /// the same, that image as a placeholder, and the texture is tried again.
+    pub fn paint<T: CallbackTarget<Context = C>>(
+        &mut self,
+        canvas: &mut Canvas<T>,
+    ) -> Result<(), PaintError> {
+        self.paint_for(canvas, true)
+    }
+
+    /// [`Self::paint`], saying whether the frame is turned when it is presented.
+    /// One that is not, for want of somewhere to turn it, is on `canvas` as the
+    /// window shows it, so a screenshot is read at the window's size.
+    pub(crate) fn paint_for<T: CallbackTarget<Context = C>>(
+        &mut self,
+        canvas: &mut Canvas<T>,
+        turned_at_present: bool,
+    ) -> Result<(), PaintError> {
         let pixels_per_point = self.run_output.pixels_per_point;
         let (mut textures_delta, shapes) = self.run_output.take();
         let clipped_primitives = self.ctx.tessellate(shapes, pixels_per_point);
-        if let Err(e) = self.painter.paint_and_update_textures(
+        let painted = self.painter.paint_and_update_textures(
             canvas,
             pixels_per_point,
             &mut textures_delta,
             clipped_primitives,
-        ) {
-            log::error!("Failed to paint: {e}");
+        );
+        let screenshots = std::mem::take(&mut self.run_output.screenshots);
+        if !screenshots.is_empty() {
+            self.read_screenshot(canvas, screenshots, turned_at_present);
         }
+        // Uploads held back by the budget go up with later frames, which an
+        // idle egui would otherwise never ask for.
+        if self.painter.has_pending_uploads() {
+            self.ctx.request_repaint();
+        }
+        painted
+    }
+
+    /// Read the frame just painted back for the screenshots egui asked for. If
+    /// `turned_at_present`, it is still the screen egui laid out: the turn
+    /// happens when it is presented.
+    fn read_screenshot<T: RenderTarget>(
+        &mut self,
+        canvas: &Canvas<T>,
+        screenshots: Vec<egui::UserData>,
+        turned_at_present: bool,
+    ) {
+        let (width, height) = self.state.get_drawable_size();
+        let drawable = egui::vec2(width as f32, height as f32);
+        let screen = if turned_at_present {
+            self.state.rotation().screen_size(drawable)
+        } else {
+            drawable
+        };
+        let (width, height) = (screen.x as u32, screen.y as u32);
+        let rect = sdl2::rect::Rect::new(0, 0, width, height);
+        match canvas.read_pixels(rect, sdl2::pixels::PixelFormatEnum::RGBA32) {
+            Ok(rgba) => {
+                let size = [width as usize, height as usize];
+                let image =
+                    std::sync::Arc::new(egui::ColorImage::from_rgba_unmultiplied(size, &rgba));
+                for user_data in screenshots {
+                    self.state.on_screenshot(user_data, image.clone());
+                }
+            }
+            Err(e) => log::warn!("could not read the frame back for a screenshot: {e}"),
+        }
+    }
+
+    /// Show an SDL texture the app renders into as an egui image (see
+    /// [`Painter::register_native_texture`]).
+    pub fn register_native_texture(&mut self, texture: sdl2::render::Texture) -> egui::TextureId {
+        self.painter.register_native_texture(texture)
+    }
+
+    /// Make `id` show `texture`, destroying the texture it showed (see
+    /// [`Painter::replace_native_texture`]).
+    pub fn replace_native_texture(&mut self, id: egui::TextureId, texture: sdl2::render::Texture) {
+        self.painter.replace_native_texture(id, texture);
+    }
+
+    /// Destroy a texture registered with [`Self::register_native_texture`].
+    pub fn free_texture(&mut self, id: egui::TextureId) {
+        self.painter.free_texture(&id);
     }
 
     /// Call to release the allocated graphics resources.
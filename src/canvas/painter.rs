@@ -3,6 +3,7 @@
 //! This module provides [`Painter`], which integrates egui rendering with an
 //! SDL2 [`Canvas`] — a window's, or a surface's when the app draws offscreen.
 
+use super::tiles::{self, Grid};
 use egui::epaint::{ImageDelta, Primitive};
 use egui::{ClippedPrimitive, ImageData, TexturesDelta};
 use sdl2::pixels::PixelFormatEnum;
@@ -11,7 +12,8 @@ use sdl2::render::{BlendMode, Canvas, RenderTarget, Texture, TextureCreator};
 use sdl2::surface::{Surface, SurfaceContext};
 use sdl2::sys::{SDL_Color, SDL_FPoint, SDL_Vertex};
 use sdl2::video::{Window, WindowContext};
-use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::os::raw::c_int;
 
 /// The format egui's own pixels are already in: an upload into it is a copy.
@@ -23,6 +25,186 @@ pub const DEFAULT_FORMAT: PixelFormatEnum = PixelFormatEnum::RGBA8888;
 
 pub(crate) const BYTES_PER_PIXEL: usize = 4;
 
+/// What a texture SDL would not give us is drawn in instead: loud on purpose,
+/// so a missing image reads as missing rather than as a blank panel.
+const PLACEHOLDER: [u8; 3] = [255, 0, 255];
+
+/// Frames between attempts at a texture SDL refused, about half a second at
+/// 60 fps. A refusal is usually a size or memory limit that the next frame
+/// will hit too, and the attempt costs an allocation.
+const RETRY_INTERVAL: u32 = 30;
+
+/// Where the painter's own `TextureId::User` ids start, as egui_glow's do: far
+/// above the small ones an app mints for textures of its own.
+pub(crate) const FIRST_NATIVE_ID: u64 = 1 << 32;
+
+/// A texture the renderer would not create or fill. The frame it happened in
+/// still painted: the image shows as a placeholder and is tried again.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PaintError {
+    /// SDL refused a texture of this size — past the driver's limit, or out of
+    /// video memory.
+    CreateTexture {
+        id: egui::TextureId,
+        width: u32,
+        height: u32,
+        reason: String,
+    },
+    /// SDL refused the pixels for a texture it had created.
+    UpdateTexture { id: egui::TextureId, reason: String },
+}
+
+impl PaintError {
+    /// The egui texture it happened to.
+    pub fn texture_id(&self) -> egui::TextureId {
+        match self {
+            PaintError::CreateTexture { id, .. } | PaintError::UpdateTexture { id, .. } => *id,
+        }
+    }
+}
+
+impl std::fmt::Display for PaintError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PaintError::CreateTexture {
+                id,
+                width,
+                height,
+                reason,
+            } => write!(
+                f,
+                "failed to create a {width}x{height} texture for {id:?}: {reason}"
+            ),
+            PaintError::UpdateTexture { id, reason } => {
+                write!(f, "failed to upload {id:?}: {reason}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PaintError {}
+
+/// The texture egui's glyphs are in. Exempt from the budgets: egui lays text
+/// out against atlas patches of the same frame, so a late one shows as garbage.
+const FONT_ATLAS: egui::TextureId = egui::TextureId::Managed(0);
+
+/// A texture egui gave us, as SDL holds it: one SDL texture, or a grid of them
+/// for an image past the renderer's limit.
+struct CanvasTexture {
+    /// Row-major, laid out by `grid`.
+    tiles: Vec<Texture>,
+    grid: Grid,
+    /// As egui last asked for them: the filter is applied to the tiles, the
+    /// wrap mode to the meshes that sample them.
+    options: egui::TextureOptions,
+    /// What it takes up in video memory, for the texture budget.
+    bytes: usize,
+    /// The frame a mesh last sampled it in.
+    last_used: u64,
+    /// Created for a whole image that is still on its way up. Until that lands
+    /// the texture holds nothing worth drawing.
+    filling: bool,
+    /// The whole image as egui last described it, kept while a texture budget
+    /// is set so the texture can be evicted and built again.
+    source: Option<ImageDelta>,
+}
+
+/// A delta on its way to the renderer, part of which may already be there.
+struct Upload {
+    id: egui::TextureId,
+    delta: ImageDelta,
+    /// Rows of `delta` already uploaded.
+    rows_done: usize,
+}
+
+/// A texture SDL refused, and what it still owes egui.
+struct FailedTexture {
+    /// The deltas not yet applied, oldest first. A whole image replaces them.
+    pending: Vec<ImageDelta>,
+    /// Frames until the next attempt.
+    retry_in: u32,
+}
+
+/// Custom drawing in the middle of egui's, for a widget that renders its own
+/// content — an emulator screen, a waveform. Hand one to egui in an
+/// [`egui::PaintCallback`], the way `egui_glow::CallbackFn` is:
+///
+/// ```no_run
+/// # fn ui(ui: &mut egui::Ui) {
+/// use egui_sdl2::canvas::{CallbackCanvas, CallbackFn};
+///
+/// let (rect, _) = ui.allocate_exact_size(egui::vec2(160.0, 144.0), egui::Sense::hover());
+/// let callback = CallbackFn::new(|info, canvas| match canvas {
+///     CallbackCanvas::Window(canvas) => canvas.fill_rect(info.rect).unwrap(),
+///     CallbackCanvas::Surface(canvas) => canvas.fill_rect(info.rect).unwrap(),
+/// });
+/// ui.painter().add(egui::PaintCallback {
+///     rect,
+///     callback: std::sync::Arc::new(callback),
+/// });
+/// # }
+/// ```
+///
+/// It is called in egui's draw order with the clip already applied. The clip,
+/// blend mode and draw colour are put back afterwards; anything else it changes
+/// on the canvas, such as the render target, it must put back itself.
+pub struct CallbackFn {
+    f: Box<dyn Fn(CallbackInfo, CallbackCanvas<'_, '_>) + Send + Sync>,
+}
+
+impl CallbackFn {
+    pub fn new<F: Fn(CallbackInfo, CallbackCanvas<'_, '_>) + Send + Sync + 'static>(
+        callback: F,
+    ) -> Self {
+        Self {
+            f: Box::new(callback),
+        }
+    }
+}
+
+/// Where a [`CallbackFn`] draws, in the canvas's pixels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CallbackInfo {
+    /// The rect egui laid the callback out in.
+    pub rect: Rect,
+    /// The part of `rect` that is visible, already set as the canvas's clip.
+    pub clip_rect: Rect,
+    pub pixels_per_point: f32,
+}
+
+/// The canvas a [`CallbackFn`] draws on: the window's, or a surface's when the
+/// painter draws offscreen (see [`crate::Renderer::CanvasBlit`]). Most of SDL's
+/// render API is on both, so a helper generic over the target takes either arm.
+pub enum CallbackCanvas<'a, 's> {
+    Window(&'a mut Canvas<Window>),
+    Surface(&'a mut Canvas<Surface<'s>>),
+}
+
+mod sealed {
+    pub trait Sealed {}
+    impl Sealed for sdl2::video::Window {}
+    impl Sealed for sdl2::surface::Surface<'_> {}
+}
+
+/// A render target the painter draws on: a window, or any surface, borrowed
+/// pixels included. It says which [`CallbackCanvas`] arm a [`CallbackFn`] gets.
+pub trait CallbackTarget: RenderTarget + Sized + sealed::Sealed {
+    /// Hand `canvas` to `f` as this target's arm.
+    fn with_callback_canvas(canvas: &mut Canvas<Self>, f: impl FnOnce(CallbackCanvas<'_, '_>));
+}
+
+impl CallbackTarget for Window {
+    fn with_callback_canvas(canvas: &mut Canvas<Self>, f: impl FnOnce(CallbackCanvas<'_, '_>)) {
+        f(CallbackCanvas::Window(canvas))
+    }
+}
+
+impl CallbackTarget for Surface<'_> {
+    fn with_callback_canvas(canvas: &mut Canvas<Self>, f: impl FnOnce(CallbackCanvas<'_, '_>)) {
+        f(CallbackCanvas::Surface(canvas))
+    }
+}
+
 /// The format to paint in for `canvas`: [`DEFAULT_FORMAT`] where the renderer
 /// takes it, otherwise the first 32-bit format with alpha it lists. A format the
 /// renderer lacks is converted on every upload, whole-frame under
@@ -73,7 +255,27 @@ fn channel_offsets(format: PixelFormatEnum) -> Option<[usize; BYTES_PER_PIXEL]>
 ///
 /// NOTE: all egui viewports share the same painter.
 pub struct Painter<C = WindowContext> {
-    textures: HashMap<egui::TextureId, Texture>,
+    textures: HashMap<egui::TextureId, CanvasTexture>,
+    /// Textures SDL refused, drawn as a placeholder until a retry succeeds.
+    failed: HashMap<egui::TextureId, FailedTexture>,
+    /// Images evicted to stay within `texture_budget`, rebuilt when a mesh
+    /// samples them again.
+    evicted: HashMap<egui::TextureId, ImageDelta>,
+    /// Deltas waiting for room in a frame's upload budget, in egui's order.
+    uploads: VecDeque<Upload>,
+    /// Textures whose pixels the app keeps (see [`Self::set_texture_now`]): no
+    /// copy is kept of them, and so they are never evicted.
+    app_owned: HashSet<egui::TextureId>,
+    /// What `textures` may take up in video memory; `None` for no limit.
+    texture_budget: Option<usize>,
+    /// What `textures` takes up now.
+    texture_bytes: usize,
+    /// How many bytes a frame may upload; `None` for no limit.
+    upload_budget: Option<usize>,
+    /// Bytes uploaded in the current frame.
+    uploaded_this_frame: usize,
+    /// Counts `paint_and_update_textures` calls, as the clock LRU runs on.
+    frame: u64,
     texture_creator: TextureCreator<C>,
     /// Reused across meshes and frames so `paint_mesh` repacks egui vertices
     /// into SDL's layout without allocating a fresh `Vec` per mesh.
@@ -85,10 +287,18 @@ pub struct Painter<C = WindowContext> {
     last_clip: Option<Rect>,
     /// Triangles waiting to be drawn, so a run of them is still one SDL call.
     index_scratch: Vec<u32>,
+    /// The id the next [`Self::register_native_texture`] hands out, counted
+    /// from [`FIRST_NATIVE_ID`].
+    next_native_id: u64,
+    /// Triangles cut at each repeat of a texture egui wraps.
+    wrap_scratch: Vec<SDL_Vertex>,
+    /// Per-tile triangles, for meshes sampling an image held as tiles.
+    tile_scratch: Vec<Vec<SDL_Vertex>>,
     /// Reused for the straight-alpha copy an upload needs; the atlas is uploaded
     /// whole whenever it grows, which is not the frame to be allocating in.
     pixel_scratch: Vec<u8>,
-    /// This renderer's texture size limit, for egui to lay its atlas out within.
+    /// This renderer's texture size limit, for egui to lay its atlas out within
+    /// and larger images to be tiled at.
     max_texture_side: Option<usize>,
     /// The format egui's textures are held in.
     format: PixelFormatEnum,
@@ -143,9 +353,21 @@ impl<C> Painter<C> {
         };
         Self {
             textures: HashMap::new(),
+            failed: HashMap::new(),
+            evicted: HashMap::new(),
+            uploads: VecDeque::new(),
+            app_owned: HashSet::new(),
+            texture_budget: None,
+            texture_bytes: 0,
+            upload_budget: None,
+            uploaded_this_frame: 0,
+            frame: 0,
             texture_creator,
             vertex_scratch: Vec::new(),
             index_scratch: Vec::new(),
+            next_native_id: FIRST_NATIVE_ID,
+            wrap_scratch: Vec::new(),
+            tile_scratch: Vec::new(),
             pixel_scratch: Vec::new(),
             last_clip: None,
             max_texture_side,
@@ -167,34 +389,139 @@ impl<C> Painter<C> {
         self.max_texture_side
     }
 
+    /// Override the limit the renderer reported, for a driver that claims more
+    /// than it holds. An image past the limit is held as a grid of textures
+    /// each within it; images already uploaded keep their layout until egui
+    /// sends them again. Pass the same value on to
+    /// [`crate::State::set_max_texture_side`] so egui's atlas fits too.
+    pub fn set_max_texture_side(&mut self, side: Option<usize>) {
+        self.max_texture_side = side;
+    }
+
+    /// Textures SDL refused and that are waiting for another attempt. Their
+    /// meshes are painted as a placeholder meanwhile.
+    pub fn failed_textures(&self) -> impl Iterator<Item = egui::TextureId> + '_ {
+        self.failed.keys().copied()
+    }
+
+    /// Cap the video memory egui's textures take, in bytes; `None` (the
+    /// default) for no cap. Past it, the images least recently painted are
+    /// evicted and rebuilt from a CPU-side copy when they are next shown. The
+    /// copies are only kept while a budget is set; the font atlas is never
+    /// evicted. Images uploaded before the budget was set have no copy to come
+    /// back from, so they stay until egui sends them again.
+    pub fn set_texture_budget(&mut self, bytes: Option<usize>) {
+        if bytes.is_none() {
+            for texture in self.textures.values_mut() {
+                texture.source = None;
+            }
+        }
+        self.texture_budget = bytes;
+    }
+
+    pub fn texture_budget(&self) -> Option<usize> {
+        self.texture_budget
+    }
+
+    /// The video memory egui's textures take now, in bytes.
+    pub fn texture_bytes(&self) -> usize {
+        self.texture_bytes
+    }
+
+    /// Cap the bytes uploaded per frame; `None` (the default) for no cap. A
+    /// delta past it goes up a band of rows per frame, and a new image is not
+    /// drawn until it has all arrived. Every frame moves at least one row, and
+    /// the font atlas is never held back.
+    pub fn set_upload_budget(&mut self, bytes: Option<usize>) {
+        self.upload_budget = bytes;
+    }
+
+    pub fn upload_budget(&self) -> Option<usize> {
+        self.upload_budget
+    }
+
+    /// Whether uploads are waiting for a later frame. egui may be idle by then,
+    /// so ask it for a repaint while this holds.
+    pub fn has_pending_uploads(&self) -> bool {
+        !self.uploads.is_empty()
+    }
+
     /// This function must be called before [`Painter`] is dropped, as [`Painter`] has some objects
     /// that should be deleted.
     pub fn destroy(&mut self) {
         let textures = std::mem::replace(&mut self.textures, HashMap::with_capacity(0));
         for (_id, tex) in textures {
-            unsafe {
-                tex.destroy();
-            }
+            tex.destroy();
         }
+        self.texture_bytes = 0;
+        self.failed.clear();
+        self.evicted.clear();
+        self.uploads.clear();
+        self.app_owned.clear();
     }
 
     /// You are expected to have cleared the color buffer before calling this.
     ///
     /// The deltas are drained: egui 0.36 asserts on drop that every delta was
     /// handled, so applying consumes them.
-    pub fn paint_and_update_textures<T: RenderTarget<Context = C>>(
+    ///
+    /// A texture the renderer refuses does not stop the frame: everything else
+    /// is painted, the refused one as a placeholder, and it is tried again in a
+    /// later frame. The error is the frame's first such refusal.
+    pub fn paint_and_update_textures<T: CallbackTarget<Context = C>>(
         &mut self,
         canvas: &mut Canvas<T>,
         pixels_per_point: f32,
         textures_delta: &mut TexturesDelta,
         paint_jobs: Vec<ClippedPrimitive>,
-    ) -> Result<(), String> {
+    ) -> Result<(), PaintError> {
+        self.frame += 1;
+        self.uploaded_this_frame = 0;
+        // Stamped before anything is uploaded, so making room for a new image
+        // never evicts one this frame is about to draw.
+        let mut restore = Vec::new();
+        for job in &paint_jobs {
+            let Primitive::Mesh(mesh) = &job.primitive else {
+                continue;
+            };
+            match self.textures.get_mut(&mesh.texture_id) {
+                Some(texture) => texture.last_used = self.frame,
+                None if self.evicted.contains_key(&mesh.texture_id) => {
+                    restore.push(mesh.texture_id)
+                }
+                None => {}
+            }
+        }
+
+        let mut first_error = None;
+        let mut report = |result: Result<(), PaintError>| {
+            if let Err(e) = result {
+                // Only the first is returned; the rest would go unheard.
+                match first_error {
+                    Some(_) => log::warn!("{e}"),
+                    None => first_error = Some(e),
+                }
+            }
+        };
+        report(self.retry_failed());
         // egui 0.36 batches several deltas per texture; apply them in order.
         for (id, deltas) in textures_delta.set.drain() {
             for delta in deltas {
-                self.set_texture(id, &delta);
+                report(self.set_texture(id, &delta));
+            }
+        }
+        // A whole image from egui may have replaced an evicted one meanwhile.
+        for id in restore {
+            if let Some(source) = self.evicted.remove(&id) {
+                self.uploads.push_back(Upload {
+                    id,
+                    delta: source,
+                    rows_done: 0,
+                });
             }
         }
+        report(self.pump_uploads());
+        self.make_room(0);
 
         self.paint_primitives(canvas, pixels_per_point, paint_jobs);
 
@@ -202,11 +529,11 @@ impl<C> Painter<C> {
             self.free_texture(&id);
         }
 
-        Ok(())
+        first_error.map_or(Ok(()), Err)
     }
 
     /// Main entry-point for painting a frame.
-    pub fn paint_primitives<T: RenderTarget<Context = C>>(
+    pub fn paint_primitives<T: CallbackTarget<Context = C>>(
         &mut self,
         canvas: &mut Canvas<T>,
         pixels_per_point: f32,
@@ -225,9 +552,8 @@ impl<C> Painter<C> {
                 Primitive::Mesh(mesh) => {
                     self.paint_mesh(canvas, pixels_per_point, job.clip_rect, mesh)
                 }
-                Primitive::Callback(_callback) => {
-                    // TODO
-                    log::warn!("PaintCallbacks are not supported")
+                Primitive::Callback(callback) => {
+                    self.paint_callback(canvas, pixels_per_point, job.clip_rect, &callback)
                 }
             }
         }
@@ -241,22 +567,239 @@ impl<C> Painter<C> {
         canvas.set_blend_mode(caller_blend);
     }
 
-    pub fn set_texture(&mut self, id: egui::TextureId, delta: &ImageDelta) {
+    /// Run a [`CallbackFn`] over its rect, then put back what the painter relies on.
+    fn paint_callback<T: CallbackTarget<Context = C>>(
+        &mut self,
+        canvas: &mut Canvas<T>,
+        pixels_per_point: f32,
+        clip_rect: egui::Rect,
+        callback: &egui::PaintCallback,
+    ) {
+        let Some(callback_fn) = callback.callback.downcast_ref::<CallbackFn>() else {
+            log::warn!("a paint callback that is not an egui_sdl2::canvas::CallbackFn is skipped");
+            return;
+        };
+        let to_pixels = |rect: egui::Rect| {
+            let min = (rect.min * pixels_per_point).round();
+            let max = (rect.max * pixels_per_point).round();
+            (max.x > min.x && max.y > min.y).then(|| {
+                Rect::new(
+                    min.x as i32,
+                    min.y as i32,
+                    (max.x - min.x) as u32,
+                    (max.y - min.y) as u32,
+                )
+            })
+        };
+        let (Some(rect), Some(clip)) = (
+            to_pixels(callback.rect),
+            to_pixels(clip_rect.intersect(callback.rect)),
+        ) else {
+            return; // nothing of it would show
+        };
+
+        let draw_color = canvas.draw_color();
+        canvas.set_clip_rect(clip);
+        let info = CallbackInfo {
+            rect,
+            clip_rect: clip,
+            pixels_per_point,
+        };
+        T::with_callback_canvas(canvas, |target| (callback_fn.f)(info, target));
+
+        // Back to what the meshes around it are drawn with; the clip is
+        // recorded so the run still clears it at the end.
+        canvas.set_clip_rect(clip);
+        self.last_clip = Some(clip);
+        canvas.set_blend_mode(BlendMode::Blend);
+        canvas.set_draw_color(draw_color);
+    }
+
+    /// Apply one of egui's texture deltas, within what is left of this frame's
+    /// upload budget; the rest goes up in later frames. A refused one is
+    /// recorded and tried again by [`Self::paint_and_update_textures`]; its
+    /// meshes show a placeholder until then. The error is the first refusal
+    /// this call ran into, which may be an earlier delta's.
+    pub fn set_texture(
+        &mut self,
+        id: egui::TextureId,
+        delta: &ImageDelta,
+    ) -> Result<(), PaintError> {
+        if let Some(failed) = self.failed.get_mut(&id) {
+            if delta.pos.is_some() {
+                // A patch needs the image under it, which is still owed.
+                failed.pending.push(delta.clone());
+                return Ok(());
+            }
+            self.failed.remove(&id);
+        }
+        if delta.pos.is_none() {
+            self.evicted.remove(&id);
+        } else if let Some(source) = self.evicted.get_mut(&id) {
+            // Nothing on the renderer to patch; the copy it comes back from is.
+            patch_source(source, delta);
+            return Ok(());
+        }
+        if id == FONT_ATLAS {
+            let rows = delta.image.height();
+            return self
+                .upload_rows(id, delta, 0..rows)
+                .inspect_err(|_| self.record_failure(id, vec![delta.clone()]));
+        }
+        self.uploads.push_back(Upload {
+            id,
+            delta: delta.clone(),
+            rows_done: 0,
+        });
+        self.pump_uploads()
+    }
+
+    /// Apply a delta for a texture whose pixels the app keeps itself, a frame of
+    /// video say, in full and at once: past the upload budget, and with no copy
+    /// kept for the texture budget, which never evicts it. A refused one is
+    /// recorded and retried as [`Self::set_texture`]'s are.
+    pub fn set_texture_now(
+        &mut self,
+        id: egui::TextureId,
+        delta: &ImageDelta,
+    ) -> Result<(), PaintError> {
+        self.app_owned.insert(id);
+        if let Some(failed) = self.failed.get_mut(&id) {
+            if delta.pos.is_some() {
+                failed.pending.push(delta.clone());
+                return Ok(());
+            }
+            self.failed.remove(&id);
+        }
+        let rows = delta.image.height();
+        self.upload_rows(id, delta, 0..rows)
+            .inspect_err(|_| self.record_failure(id, vec![delta.clone()]))
+    }
+
+    /// Upload queued deltas until this frame's budget is spent.
+    fn pump_uploads(&mut self) -> Result<(), PaintError> {
+        let mut first_error = None;
+        while let Some(mut upload) = self.uploads.pop_front() {
+            let [width, height] = upload.delta.image.size();
+            let rows_left = height - upload.rows_done;
+            let rows = match self.upload_budget {
+                None => rows_left,
+                Some(budget) => {
+                    let row_bytes = (width * BYTES_PER_PIXEL).max(1);
+                    let room = budget.saturating_sub(self.uploaded_this_frame) / row_bytes;
+                    // A row wider than the whole budget still has to go some time.
+                    let room = if self.uploaded_this_frame == 0 {
+                        room.max(1)
+                    } else {
+                        room
+                    };
+                    room.min(rows_left)
+                }
+            };
+            if rows == 0 && rows_left > 0 {
+                self.uploads.push_front(upload);
+                break;
+            }
+            let band = upload.rows_done..upload.rows_done + rows;
+            if let Err(e) = self.upload_rows(upload.id, &upload.delta, band) {
+                // What was queued behind it for the same texture waits with it.
+                let mut pending = vec![upload.delta];
+                self.uploads.retain(|queued| {
+                    if queued.id != upload.id {
+                        return true;
+                    }
+                    pending.push(queued.delta.clone());
+                    false
+                });
+                self.record_failure(upload.id, pending);
+                first_error.get_or_insert(e);
+                continue;
+            }
+            upload.rows_done += rows;
+            if upload.rows_done < height {
+                self.uploads.push_front(upload);
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+
+    fn record_failure(&mut self, id: egui::TextureId, pending: Vec<ImageDelta>) {
+        self.failed.insert(
+            id,
+            FailedTexture {
+                pending,
+                retry_in: RETRY_INTERVAL,
+            },
+        );
+    }
+
+    /// Hand the refused textures whose turn has come back to the upload queue,
+    /// with what each still owes, in order. A refusal lands them back here.
+    fn retry_failed(&mut self) -> Result<(), PaintError> {
+        let due: Vec<egui::TextureId> = self
+            .failed
+            .iter_mut()
+            .filter_map(|(id, failed)| {
+                failed.retry_in = failed.retry_in.saturating_sub(1);
+                (failed.retry_in == 0).then_some(*id)
+            })
+            .collect();
+        for id in due {
+            let Some(failed) = self.failed.remove(&id) else {
+                continue;
+            };
+            log::debug!("retrying {id:?}");
+            self.uploads
+                .extend(failed.pending.into_iter().map(|delta| Upload {
+                    id,
+                    delta,
+                    rows_done: 0,
+                }));
+        }
+        self.pump_uploads()
+    }
+
+    /// Upload `rows` of `delta`, creating the texture with the first band of a
+    /// whole image.
+    fn upload_rows(
+        &mut self,
+        id: egui::TextureId,
+        delta: &ImageDelta,
+        rows: std::ops::Range<usize>,
+    ) -> Result<(), PaintError> {
         let ImageData::Color(img) = &delta.image;
+        let w = img.width() as u32;
+        let h = img.height() as u32;
+
+        if rows.start == 0 {
+            match delta.pos {
+                None => self.prepare_whole(id, delta, w, h)?,
+                Some(_) => {
+                    if let Some(source) = self.textures.get_mut(&id).and_then(|t| t.source.as_mut())
+                    {
+                        patch_source(source, delta);
+                    }
+                }
+            }
+        }
+        if rows.is_empty() {
+            return Ok(());
+        }
+
         // Straight alpha, to match the vertex colours: see `into_sdl_vertex`. The
         // font atlas arrives as premultiplied white coverage, and becomes white
         // with the coverage in alpha, which is what modulating a texture expects.
+        let pixels = &img.pixels[rows.start * w as usize..rows.end * w as usize];
         self.pixel_scratch.clear();
-        self.pixel_scratch
-            .reserve(img.pixels.len() * BYTES_PER_PIXEL);
+        self.pixel_scratch.reserve(pixels.len() * BYTES_PER_PIXEL);
         let [r_at, g_at, b_at, a_at] = self.channels;
         if self.channels == [0, 1, 2, 3] {
-            for pixel in img.pixels.iter() {
+            for pixel in pixels {
                 self.pixel_scratch
                     .extend_from_slice(&pixel.to_srgba_unmultiplied());
             }
         } else {
-            for pixel in img.pixels.iter() {
+            for pixel in pixels {
                 let [r, g, b, a] = pixel.to_srgba_unmultiplied();
                 let mut texel = [0u8; BYTES_PER_PIXEL];
                 texel[r_at] = r;
@@ -266,34 +809,214 @@ impl<C> Painter<C> {
                 self.pixel_scratch.extend_from_slice(&texel);
             }
         }
-        let w = img.width() as u32;
-        let h = img.height() as u32;
         let pitch = (w as usize) * BYTES_PER_PIXEL;
 
-        if delta.pos.is_none() {
-            if let Some(tex) = self.textures.get(&id) {
-                let q = tex.query();
-                if q.width != w || q.height != h {
-                    self.free_texture(&id);
+        let Some(texture) = self.textures.get_mut(&id) else {
+            return Err(PaintError::UpdateTexture {
+                id,
+                reason: "no texture to patch".to_string(),
+            });
+        };
+        // Each tile the band reaches takes its part, read in place from the
+        // band's rows at their full pitch.
+        let [x, y] = delta.pos.unwrap_or([0, 0]);
+        let (top, right, bottom) = (y + rows.start, x + w as usize, y + rows.end);
+        let reached = texture
+            .grid
+            .touching([x as f32, top as f32], [right as f32, bottom as f32]);
+        for index in reached {
+            let [tile_x, tile_y, tile_w, tile_h] = texture.grid.bounds(index);
+            let (x0, x1) = (x.max(tile_x), right.min(tile_x + tile_w));
+            let (y0, y1) = (top.max(tile_y), bottom.min(tile_y + tile_h));
+            if x0 >= x1 || y0 >= y1 {
+                continue;
+            }
+            let offset = (y0 - top) * pitch + (x0 - x) * BYTES_PER_PIXEL;
+            let rect = Rect::new(
+                (x0 - tile_x) as i32,
+                (y0 - tile_y) as i32,
+                (x1 - x0) as u32,
+                (y1 - y0) as u32,
+            );
+            texture.tiles[index]
+                .update(rect, &self.pixel_scratch[offset..], pitch)
+                .map_err(|e| PaintError::UpdateTexture {
+                    id,
+                    reason: e.to_string(),
+                })?;
+        }
+        if rows.end == h as usize {
+            texture.filling = false;
+        }
+        self.uploaded_this_frame += self.pixel_scratch.len();
+        Ok(())
+    }
+
+    /// Make sure `id` has a texture of `w`x`h` for a whole image to go into.
+    fn prepare_whole(
+        &mut self,
+        id: egui::TextureId,
+        delta: &ImageDelta,
+        w: u32,
+        h: u32,
+    ) -> Result<(), PaintError> {
+        if let Some(texture) = self.textures.get(&id) {
+            if texture.grid.size != [w as usize, h as usize] {
+                self.free_texture(&id);
+            }
+        }
+        let bytes = w as usize * h as usize * BYTES_PER_PIXEL;
+        if !self.textures.contains_key(&id) {
+            self.make_room(bytes);
+        }
+        let texture = match self.textures.entry(id) {
+            // Same size: the old image shows until the new one has replaced it.
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let grid = Grid::new([w as usize, h as usize], self.max_texture_side);
+                let mut tiles = Vec::with_capacity(grid.len());
+                for index in 0..grid.len() {
+                    let [_, _, tile_w, tile_h] = grid.bounds(index);
+                    let tile = create_texture(
+                        &self.texture_creator,
+                        tile_w as u32,
+                        tile_h as u32,
+                        self.format,
+                    );
+                    match tile {
+                        Ok(tile) => tiles.push(tile),
+                        Err(reason) => {
+                            for tile in tiles {
+                                unsafe { tile.destroy() }
+                            }
+                            return Err(PaintError::CreateTexture {
+                                id,
+                                width: w,
+                                height: h,
+                                reason,
+                            });
+                        }
+                    }
+                }
+                for tile in &tiles {
+                    set_filter(tile, delta.options);
                 }
+                self.texture_bytes += bytes;
+                entry.insert(CanvasTexture {
+                    tiles,
+                    grid,
+                    options: delta.options,
+                    bytes,
+                    last_used: self.frame,
+                    filling: true,
+                    source: None,
+                })
+            }
+        };
+        if texture.options != delta.options {
+            for tile in &texture.tiles {
+                set_filter(tile, delta.options);
             }
+            texture.options = delta.options;
+        }
+        if self.texture_budget.is_some() && id != FONT_ATLAS && !self.app_owned.contains(&id) {
+            // Shares egui's pixels rather than copying them.
+            texture.source = Some(delta.clone());
         }
+        Ok(())
+    }
 
-        let format = self.format;
-        let tex = self
-            .textures
-            .entry(id)
-            .or_insert_with(|| create_texture(&self.texture_creator, w, h, format));
-        let rect = delta.pos.map(|[x, y]| Rect::new(x as i32, y as i32, w, h));
-        tex.update(rect, &self.pixel_scratch, pitch).unwrap();
+    /// Evict the images least recently painted until `bytes` more fit in the
+    /// texture budget. Nothing this frame draws goes, nor anything with a patch
+    /// still queued for it, so a frame that needs more than the budget runs
+    /// over it rather than thrashing.
+    fn make_room(&mut self, bytes: usize) {
+        let Some(budget) = self.texture_budget else {
+            return;
+        };
+        while self.texture_bytes + bytes > budget {
+            let victim = self
+                .textures
+                .iter()
+                .filter(|(id, t)| {
+                    t.last_used < self.frame
+                        && !t.filling
+                        && t.source.is_some()
+                        && !self.uploads.iter().any(|upload| upload.id == **id)
+                })
+                .min_by_key(|(_, t)| t.last_used)
+                .map(|(id, _)| *id);
+            let Some(id) = victim else {
+                log::debug!(
+                    "{} bytes of textures against a budget of {budget}: nothing left to evict",
+                    self.texture_bytes + bytes
+                );
+                return;
+            };
+            if let Some(mut texture) = self.textures.remove(&id) {
+                self.texture_bytes -= texture.bytes;
+                if let Some(source) = texture.source.take() {
+                    self.evicted.insert(id, source);
+                }
+                texture.destroy();
+            }
+        }
+    }
+
+    /// Show a texture the app rendered into as an egui image, sampled in place
+    /// with no copy. Its blend and scale modes are its own; the painter only
+    /// sets its colour and alpha mod for the tint egui draws it with. The
+    /// painter owns it from here: [`Self::free_texture`] destroys it, and so
+    /// does [`Self::destroy`]. It takes no part in the texture budget.
+    ///
+    /// `texture` must come from the renderer this painter paints with.
+    pub fn register_native_texture(&mut self, texture: Texture) -> egui::TextureId {
+        let id = self.next_texture_id();
+        self.replace_native_texture(id, texture);
+        id
+    }
+
+    /// A `TextureId::User` of the painter's own, for a texture the app hands
+    /// over, clear of the ones it hands out itself.
+    pub(crate) fn next_texture_id(&mut self) -> egui::TextureId {
+        let id = egui::TextureId::User(self.next_native_id);
+        self.next_native_id += 1;
+        id
+    }
+
+    /// Make `id` show `texture` instead, destroying the one it showed — for
+    /// an app that recreates its texture, on a resize say.
+    pub fn replace_native_texture(&mut self, id: egui::TextureId, texture: Texture) {
+        self.free_texture(&id);
+        let query = texture.query();
+        let grid = Grid::new([query.width as usize, query.height as usize], None);
+        self.textures.insert(
+            id,
+            CanvasTexture {
+                tiles: vec![texture],
+                grid,
+                // Filtered as the app set it; only the wrap mode is ours.
+                options: egui::TextureOptions::default(),
+                bytes: 0,
+                last_used: self.frame,
+                filling: false,
+                // With nothing to rebuild from, it is never evicted.
+                source: None,
+            },
+        );
     }
 
+    /// Forget `id` and destroy its texture: egui's own as egui frees them, a
+    /// native one when the app is done with it.
     #[inline]
     pub fn free_texture(&mut self, id: &egui::TextureId) {
+        self.failed.remove(id);
+        self.evicted.remove(id);
+        self.app_owned.remove(id);
+        self.uploads.retain(|upload| upload.id != *id);
         if let Some(tex) = self.textures.remove(id) {
-            unsafe {
-                tex.destroy();
-            }
+            self.texture_bytes -= tex.bytes;
+            tex.destroy();
         }
     }
 
@@ -303,16 +1026,46 @@ impl<C> Painter<C> {
         canvas: &mut Canvas<T>,
         pixels_per_point: f32,
         clip_rect: egui::Rect,
-        mesh: egui::Mesh,
+        mut mesh: egui::Mesh,
     ) {
         // egui may draw untextured shapes (nullptr in SDL_RenderGeometry).
+        let mut tiled = None;
+        let wrap = self
+            .textures
+            .get(&mesh.texture_id)
+            .map_or(egui::TextureWrapMode::ClampToEdge, |tex| {
+                tex.options.wrap_mode
+            });
         let (texture_ptr, texture_size) = match self.textures.get(&mesh.texture_id) {
+            // Nothing in it yet; the image appears once its upload completes.
+            Some(tex) if tex.filling => return,
+            Some(tex) if tex.grid.len() > 1 => {
+                tiled = Some(TileView {
+                    textures: tex.tiles.iter().map(|tile| tile.raw()).collect(),
+                    grid: tex.grid,
+                });
+                (std::ptr::null_mut(), None)
+            }
             Some(tex) => {
-                let q = tex.query();
-                (tex.raw(), Some((q.width as f32, q.height as f32)))
+                let [w, h] = tex.grid.size;
+                (tex.tiles[0].raw(), Some((w as f32, h as f32)))
+            }
+            // Evicted and on its way back, or queued behind the upload budget.
+            None if self.evicted.contains_key(&mesh.texture_id)
+                || self.uploads.iter().any(|u| u.id == mesh.texture_id) =>
+            {
+                return
             }
             None => (std::ptr::null_mut(), None),
         };
+        // An image SDL refused is filled in the placeholder colour instead, at
+        // the coverage egui gave it, so the rest of the frame stays as laid out.
+        if texture_ptr.is_null() && tiled.is_none() && self.failed.contains_key(&mesh.texture_id) {
+            let [r, g, b] = PLACEHOLDER;
+            for vertex in &mut mesh.vertices {
+                vertex.color = egui::Color32::from_rgba_unmultiplied(r, g, b, vertex.color.a());
+            }
+        }
 
         let min = clip_rect.min * pixels_per_point;
         let max = clip_rect.max * pixels_per_point;
@@ -334,15 +1087,35 @@ impl<C> Painter<C> {
         // the triangle path. Flushing before each blit keeps egui's draw order.
         self.index_scratch.clear();
         for corners in mesh.indices.chunks(6) {
-            match as_axis_aligned_quad(&mesh.vertices, corners, pixels_per_point) {
+            let quad = as_axis_aligned_quad(&mesh.vertices, corners, pixels_per_point)
+                // A blit clamps; a repeat is cut up on the triangle path.
+                .filter(|quad| wrap == egui::TextureWrapMode::ClampToEdge || !quad.repeats());
+            match quad {
                 Some(quad) => {
-                    self.flush_triangles(canvas, texture_ptr, &mesh, pixels_per_point);
-                    quad.blit(canvas, texture_ptr, texture_size);
+                    self.flush_triangles(
+                        canvas,
+                        texture_ptr,
+                        tiled.as_ref(),
+                        wrap,
+                        &mesh,
+                        pixels_per_point,
+                    );
+                    match &tiled {
+                        Some(tiles) => quad.blit_tiles(canvas, tiles),
+                        None => quad.blit(canvas, texture_ptr, texture_size),
+                    }
                 }
                 None => self.index_scratch.extend_from_slice(corners),
             }
         }
-        self.flush_triangles(canvas, texture_ptr, &mesh, pixels_per_point);
+        self.flush_triangles(
+            canvas,
+            texture_ptr,
+            tiled.as_ref(),
+            wrap,
+            &mesh,
+            pixels_per_point,
+        );
     }
 
     /// Draw whatever indices have accumulated in `index_scratch` as triangles.
@@ -350,6 +1123,8 @@ impl<C> Painter<C> {
         &mut self,
         canvas: &mut Canvas<T>,
         texture_ptr: *mut sdl2_sys::SDL_Texture,
+        tiled: Option<&TileView>,
+        wrap: egui::TextureWrapMode,
         mesh: &egui::Mesh,
         pixels_per_point: f32,
     ) {
@@ -393,6 +1168,29 @@ impl<C> Painter<C> {
                 }
             }
         }
+        // SDL clamps texture coordinates. A repeat is cut into pieces that
+        // each sample one copy, and those are drawn instead.
+        let repeats = self.vertex_scratch.iter().any(|v| {
+            !(0.0..=1.0).contains(&v.tex_coord.x) || !(0.0..=1.0).contains(&v.tex_coord.y)
+        });
+        if wrap != egui::TextureWrapMode::ClampToEdge && repeats {
+            let mirrored = wrap == egui::TextureWrapMode::MirroredRepeat;
+            self.wrap_scratch.clear();
+            for triangle in self.index_scratch.chunks_exact(3) {
+                let corners = [0, 1, 2].map(|k| self.vertex_scratch[triangle[k] as usize]);
+                tiles::wrap(corners, mirrored, &mut self.wrap_scratch);
+            }
+            std::mem::swap(&mut self.vertex_scratch, &mut self.wrap_scratch);
+            self.index_scratch.clear();
+            self.index_scratch
+                .extend(0..self.vertex_scratch.len() as u32);
+        }
+
+        if let Some(tiles) = tiled {
+            self.draw_across_tiles(canvas, tiles);
+            self.index_scratch.clear();
+            return;
+        }
         let verts_len = self.vertex_scratch.len() as c_int;
         let indcs_len = self.index_scratch.len() as c_int;
 
@@ -416,6 +1214,75 @@ impl<C> Painter<C> {
             log::error!("SDL_RenderGeometry failed: {}", result);
         }
     }
+
+    /// Draw the triangles in `index_scratch` over an image held as tiles: each
+    /// is cut along the seams it crosses, and every tile draws its pieces in
+    /// one SDL call. Pieces of different tiles never overlap, so the order
+    /// between tiles does not show.
+    fn draw_across_tiles<T: RenderTarget<Context = C>>(
+        &mut self,
+        canvas: &mut Canvas<T>,
+        tiles: &TileView,
+    ) {
+        let grid = tiles.grid;
+        let (width, height) = (grid.size[0] as f32, grid.size[1] as f32);
+        self.tile_scratch.resize_with(grid.len(), Vec::new);
+        for triangle in self.index_scratch.chunks_exact(3) {
+            let corners = [0, 1, 2].map(|k| self.vertex_scratch[triangle[k] as usize]);
+            let mut min = [f32::MAX; 2];
+            let mut max = [f32::MIN; 2];
+            for corner in &corners {
+                let texel = [corner.tex_coord.x * width, corner.tex_coord.y * height];
+                for axis in 0..2 {
+                    min[axis] = min[axis].min(texel[axis]);
+                    max[axis] = max[axis].max(texel[axis]);
+                }
+            }
+            for index in grid.touching(min, max) {
+                tiles::clip_to_tile(
+                    corners,
+                    grid.uv_bounds(index),
+                    &mut self.tile_scratch[index],
+                );
+            }
+        }
+        for (vertices, &texture) in self.tile_scratch.iter_mut().zip(&tiles.textures) {
+            if vertices.is_empty() {
+                continue;
+            }
+            let result = unsafe {
+                sdl2_sys::SDL_SetTextureColorMod(texture, 255, 255, 255);
+                sdl2_sys::SDL_SetTextureAlphaMod(texture, 255);
+                sdl2_sys::SDL_RenderGeometry(
+                    canvas.raw(),
+                    texture,
+                    vertices.as_ptr(),
+                    vertices.len() as c_int,
+                    std::ptr::null(),
+                    0,
+                )
+            };
+            vertices.clear();
+            if result != 0 {
+                log::error!("SDL_RenderGeometry failed: {}", result);
+            }
+        }
+    }
+}
+
+impl CanvasTexture {
+    fn destroy(self) {
+        for tile in self.tiles {
+            unsafe { tile.destroy() }
+        }
+    }
+}
+
+/// The tiles of an image a mesh samples, taken out of the painter so drawing
+/// can borrow it mutably. The textures stay owned by their `CanvasTexture`.
+struct TileView {
+    textures: Vec<*mut sdl2_sys::SDL_Texture>,
+    grid: Grid,
 }
 
 /// An axis-aligned, single-colour quad: a glyph, or a plain rectangle.
@@ -428,6 +1295,15 @@ struct Quad {
 }
 
 impl Quad {
+    /// Whether it samples past the texture's edge, which only wrapping covers.
+    fn repeats(&self) -> bool {
+        self.textured
+            && (self.uv.min.x < 0.0
+                || self.uv.min.y < 0.0
+                || self.uv.max.x > 1.0
+                || self.uv.max.y > 1.0)
+    }
+
     fn blit<T: RenderTarget>(
         &self,
         canvas: &mut Canvas<T>,
@@ -458,6 +1334,49 @@ impl Quad {
             log::error!("blitting a quad failed: {result}");
         }
     }
+
+    /// [`Self::blit`] from an image held as tiles: one copy per tile the quad
+    /// samples, each over its share of the destination.
+    fn blit_tiles<T: RenderTarget>(&self, canvas: &mut Canvas<T>, tiles: &TileView) {
+        if !self.textured {
+            return self.blit(canvas, std::ptr::null_mut(), None);
+        }
+        let [r, g, b, a] = self.color.to_srgba_unmultiplied();
+        let (width, height) = (tiles.grid.size[0] as f32, tiles.grid.size[1] as f32);
+        let (u0, v0) = (self.uv.min.x * width, self.uv.min.y * height);
+        let (u1, v1) = (self.uv.max.x * width, self.uv.max.y * height);
+        let across = |u: f32| self.dst.x + (u - u0) / (u1 - u0) * self.dst.w;
+        let down = |v: f32| self.dst.y + (v - v0) / (v1 - v0) * self.dst.h;
+        for index in tiles.grid.touching([u0, v0], [u1, v1]) {
+            let [x, y, w, h] = tiles.grid.bounds(index).map(|n| n as f32);
+            let (left, right) = (u0.max(x), u1.min(x + w));
+            let (top, bottom) = (v0.max(y), v1.min(y + h));
+            if left >= right || top >= bottom {
+                continue;
+            }
+            let texture = tiles.textures[index];
+            let src = sdl2_sys::SDL_Rect {
+                x: (left - x).round() as i32,
+                y: (top - y).round() as i32,
+                w: (right - left).round() as i32,
+                h: (bottom - top).round() as i32,
+            };
+            let dst = sdl2_sys::SDL_FRect {
+                x: across(left),
+                y: down(top),
+                w: across(right) - across(left),
+                h: down(bottom) - down(top),
+            };
+            let result = unsafe {
+                sdl2_sys::SDL_SetTextureColorMod(texture, r, g, b);
+                sdl2_sys::SDL_SetTextureAlphaMod(texture, a);
+                sdl2_sys::SDL_RenderCopyF(canvas.raw(), texture, &src, &dst)
+            };
+            if result != 0 {
+                log::error!("blitting a quad failed: {result}");
+            }
+        }
+    }
 }
 
 /// The two triangles of an unrotated, single-colour quad, if that is what these
@@ -518,6 +1437,29 @@ fn as_axis_aligned_quad(
     })
 }
 
+/// Write a patch into the whole image a texture is rebuilt from.
+fn patch_source(source: &mut ImageDelta, patch: &ImageDelta) {
+    let (ImageData::Color(image), ImageData::Color(patch_image), Some([x, y])) =
+        (&mut source.image, &patch.image, patch.pos)
+    else {
+        return;
+    };
+    let image = std::sync::Arc::make_mut(image);
+    let [width, height] = image.size;
+    let [patch_width, _] = patch_image.size;
+    if x >= width || patch_width == 0 {
+        return;
+    }
+    let span = patch_width.min(width - x);
+    for (row, line) in patch_image.pixels.chunks_exact(patch_width).enumerate() {
+        if y + row >= height {
+            break;
+        }
+        let at = (y + row) * width + x;
+        image.pixels[at..at + span].copy_from_slice(&line[..span]);
+    }
+}
+
 /// SDL leaves the fields at 0 for drivers with no limit, its software one included.
 fn max_texture_side<T: RenderTarget>(canvas: &Canvas<T>) -> Option<usize> {
     let info = canvas.info();
@@ -530,24 +1472,35 @@ fn max_texture_side<T: RenderTarget>(canvas: &Canvas<T>) -> Option<usize> {
     Some(side as usize)
 }
 
+/// Filter `texture` as `options` ask. SDL has one mode for both directions;
+/// magnification decides, being what pixel art and zoomed photos are about.
+fn set_filter(texture: &Texture, options: egui::TextureOptions) {
+    let mode = match options.magnification {
+        egui::TextureFilter::Nearest => sdl2_sys::SDL_ScaleMode::SDL_ScaleModeNearest,
+        egui::TextureFilter::Linear => sdl2_sys::SDL_ScaleMode::SDL_ScaleModeLinear,
+    };
+    // sdl2's `set_scale_mode` panics where this renderer merely filters its own way.
+    if unsafe { sdl2_sys::SDL_SetTextureScaleMode(texture.raw(), mode) } != 0 {
+        log::warn!("{options:?} not applied: {}", sdl2::get_error());
+    }
+}
+
+/// A streaming texture for egui's pixels, or one tile of them. Refused past the
+/// renderer's limit — if that is lower than the driver reported — or when video
+/// memory runs out.
 #[inline]
 fn create_texture<C>(
     texture_creator: &TextureCreator<C>,
     w: u32,
     h: u32,
     format: PixelFormatEnum,
-) -> Texture {
+) -> Result<Texture, String> {
     let mut tex = texture_creator
         .create_texture_streaming(format, w, h)
-        .unwrap_or_else(|e| {
-            // Reached only if egui asked for more than the renderer's limit,
-            // which `Painter::max_texture_side` exists to prevent — so the
-            // integration failed to pass it on.
-            panic!("failed to create a {w}x{h} sdl2 texture: {e}")
-        });
+        .map_err(|e| e.to_string())?;
     tex.set_blend_mode(BlendMode::Blend);
 
-    tex
+    Ok(tex)
 }
 /// egui's colours are premultiplied; SDL's `BLEND` is `src*a + dst*(1-a)`, which
 /// multiplies by alpha a second time. Undo the premultiplication and the two
@@ -0,0 +1,310 @@
+//! Images larger than the renderer takes, held as a grid of textures: where the
+//! tiles sit in the image, and how a triangle sampling across them is cut into
+//! pieces that each sample one. A texture egui repeats is cut the same way, at
+//! each repeat's edge.
+
+use sdl2::sys::{SDL_Color, SDL_FPoint, SDL_Vertex};
+
+/// How an image is cut into tiles no larger than the renderer's limit: square,
+/// row-major from the top left, the last column and row taking what is left.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Grid {
+    /// The whole image, in texels.
+    pub size: [usize; 2],
+    /// A full tile's edge, in texels.
+    pub side: usize,
+    pub columns: usize,
+    pub rows: usize,
+}
+
+impl Grid {
+    /// `max_side` is the renderer's limit, `None` if it has none — and then the
+    /// grid is a single tile, which is every image that fits.
+    pub fn new(size: [usize; 2], max_side: Option<usize>) -> Self {
+        let longest = size[0].max(size[1]).max(1);
+        let side = max_side
+            .filter(|&s| s > 0)
+            .map_or(longest, |s| s.min(longest));
+        Self {
+            size,
+            side,
+            columns: size[0].div_ceil(side).max(1),
+            rows: size[1].div_ceil(side).max(1),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.columns * self.rows
+    }
+
+    /// Tile `index`'s texels: x, y, width, height.
+    pub fn bounds(&self, index: usize) -> [usize; 4] {
+        let (column, row) = (index % self.columns, index / self.columns);
+        let (x, y) = (column * self.side, row * self.side);
+        [
+            x,
+            y,
+            self.side.min(self.size[0].saturating_sub(x)),
+            self.side.min(self.size[1].saturating_sub(y)),
+        ]
+    }
+
+    /// The tiles a texel rect from `min` to `max` reaches into, row-major. A
+    /// rect with no area still touches the tile it sits in.
+    pub fn touching(&self, min: [f32; 2], max: [f32; 2]) -> impl Iterator<Item = usize> {
+        let side = self.side as f32;
+        let span = |lo: f32, hi: f32, count: usize| {
+            let first = ((lo / side).floor().max(0.0) as usize).min(count - 1);
+            let last = ((hi / side).ceil() as usize).saturating_sub(1);
+            first..=last.clamp(first, count - 1)
+        };
+        let columns = span(min[0], max[0], self.columns);
+        let rows = span(min[1], max[1], self.rows);
+        let stride = self.columns;
+        rows.flat_map(move |row| columns.clone().map(move |column| row * stride + column))
+    }
+
+    /// Tile `index` in the normalized coordinates egui samples the image in.
+    pub fn uv_bounds(&self, index: usize) -> [f32; 4] {
+        let [x, y, w, h] = self.bounds(index);
+        let (width, height) = (self.size[0] as f32, self.size[1] as f32);
+        [
+            x as f32 / width,
+            y as f32 / height,
+            (x + w) as f32 / width,
+            (y + h) as f32 / height,
+        ]
+    }
+}
+
+/// The part of `triangle` that samples within `uv` (min u, min v, max u, max
+/// v), as a list of triangles appended to `out`, their texture coordinates
+/// made relative to that rect. Nothing is appended if they do not meet.
+pub(crate) fn clip_to_tile(triangle: [SDL_Vertex; 3], uv: [f32; 4], out: &mut Vec<SDL_Vertex>) {
+    let [u0, v0, u1, v1] = uv;
+    let mut polygon = triangle.to_vec();
+    // Sutherland–Hodgman, one edge of the tile at a time: the texture axis it
+    // cuts across, where, and which side of it stays.
+    let coordinate = |v: &SDL_Vertex, axis: usize| match axis {
+        0 => v.tex_coord.x,
+        _ => v.tex_coord.y,
+    };
+    let edges = [(0, u0, true), (0, u1, false), (1, v0, true), (1, v1, false)];
+    for (axis, limit, keep_above) in edges {
+        let inside = |v: &SDL_Vertex| {
+            let c = coordinate(v, axis);
+            if keep_above {
+                c >= limit
+            } else {
+                c <= limit
+            }
+        };
+        let input = std::mem::take(&mut polygon);
+        for (i, current) in input.iter().enumerate() {
+            let next = &input[(i + 1) % input.len()];
+            if inside(current) {
+                polygon.push(*current);
+            }
+            if inside(current) != inside(next) {
+                let (from, to) = (coordinate(current, axis), coordinate(next, axis));
+                let t = (limit - from) / (to - from);
+                polygon.push(lerp(current, next, t));
+            }
+        }
+        if polygon.len() < 3 {
+            return;
+        }
+    }
+
+    let (width, height) = (u1 - u0, v1 - v0);
+    for vertex in &mut polygon {
+        vertex.tex_coord.x = (vertex.tex_coord.x - u0) / width;
+        vertex.tex_coord.y = (vertex.tex_coord.y - v0) / height;
+    }
+    for i in 1..polygon.len() - 1 {
+        out.extend_from_slice(&[polygon[0], polygon[i], polygon[i + 1]]);
+    }
+}
+
+/// Past this many repeats a triangle is drawn uncut and SDL clamps it: a
+/// background tiled that finely is a pattern egui should be given as an image.
+const MAX_REPEATS: i64 = 1024;
+
+/// `triangle` cut at every whole texture coordinate it crosses, for a texture
+/// egui repeats past 0..1 where SDL only clamps. Each piece samples a single
+/// repeat, its coordinates brought back into 0..1 and, `mirrored`, flipped on
+/// every other one. Appended to `out` as a list of triangles.
+pub(crate) fn wrap(triangle: [SDL_Vertex; 3], mirrored: bool, out: &mut Vec<SDL_Vertex>) {
+    let span = |coordinate: fn(&SDL_Vertex) -> f32| {
+        let values = triangle.map(|v| coordinate(&v));
+        let lo = values.iter().copied().fold(f32::MAX, f32::min).floor() as i64;
+        let hi = values.iter().copied().fold(f32::MIN, f32::max).ceil() as i64;
+        lo..hi.max(lo + 1)
+    };
+    let (columns, rows) = (span(|v| v.tex_coord.x), span(|v| v.tex_coord.y));
+    if (columns.end - columns.start) * (rows.end - rows.start) > MAX_REPEATS {
+        out.extend_from_slice(&triangle);
+        return;
+    }
+    for row in rows {
+        for column in columns.clone() {
+            let start = out.len();
+            let cell = [
+                column as f32,
+                row as f32,
+                (column + 1) as f32,
+                (row + 1) as f32,
+            ];
+            clip_to_tile(triangle, cell, out);
+            if mirrored {
+                for vertex in &mut out[start..] {
+                    if column.rem_euclid(2) == 1 {
+                        vertex.tex_coord.x = 1.0 - vertex.tex_coord.x;
+                    }
+                    if row.rem_euclid(2) == 1 {
+                        vertex.tex_coord.y = 1.0 - vertex.tex_coord.y;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A point along the edge from `a` to `b`. Colours blend straight, the way
+/// SDL interpolates them across the triangle.
+fn lerp(a: &SDL_Vertex, b: &SDL_Vertex, t: f32) -> SDL_Vertex {
+    let mix = |a: f32, b: f32| a + (b - a) * t;
+    let channel = |a: u8, b: u8| mix(a as f32, b as f32).round() as u8;
+    SDL_Vertex {
+        position: SDL_FPoint {
+            x: mix(a.position.x, b.position.x),
+            y: mix(a.position.y, b.position.y),
+        },
+        color: SDL_Color {
+            r: channel(a.color.r, b.color.r),
+            g: channel(a.color.g, b.color.g),
+            b: channel(a.color.b, b.color.b),
+            a: channel(a.color.a, b.color.a),
+        },
+        tex_coord: SDL_FPoint {
+            x: mix(a.tex_coord.x, b.tex_coord.x),
+            y: mix(a.tex_coord.y, b.tex_coord.y),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vertex(x: f32, y: f32, u: f32, v: f32) -> SDL_Vertex {
+        SDL_Vertex {
+            position: SDL_FPoint { x, y },
+            color: SDL_Color {
+                r: 255,
+                g: 255,
+                b: 255,
+                a: 255,
+            },
+            tex_coord: SDL_FPoint { x: u, y: v },
+        }
+    }
+
+    fn area(triangles: &[SDL_Vertex]) -> f32 {
+        triangles
+            .chunks_exact(3)
+            .map(|t| {
+                let (a, b, c) = (t[0].position, t[1].position, t[2].position);
+                ((b.x - a.x) * (c.y - a.y) - (c.x - a.x) * (b.y - a.y)).abs() / 2.0
+            })
+            .sum()
+    }
+
+    #[test]
+    fn an_image_that_fits_is_one_tile() {
+        let grid = Grid::new([640, 480], Some(2048));
+        assert_eq!(grid.len(), 1);
+        assert_eq!(grid.bounds(0), [0, 0, 640, 480]);
+        assert_eq!(Grid::new([5000, 5000], None).len(), 1);
+    }
+
+    #[test]
+    fn the_last_column_and_row_take_what_is_left() {
+        // A 4000x1200 photo on a driver that takes 1080 texels a side.
+        let grid = Grid::new([4000, 1200], Some(1080));
+        assert_eq!((grid.columns, grid.rows), (4, 2));
+        assert_eq!(grid.bounds(3), [3240, 0, 760, 1080]);
+        assert_eq!(grid.bounds(7), [3240, 1080, 760, 120]);
+    }
+
+    #[test]
+    fn a_rect_touches_the_tiles_it_reaches_into() {
+        let grid = Grid::new([300, 200], Some(100));
+        let touched = |min, max| grid.touching(min, max).collect::<Vec<_>>();
+        assert_eq!(touched([0.0, 0.0], [300.0, 200.0]), [0, 1, 2, 3, 4, 5]);
+        // Ending on a seam does not reach over it.
+        assert_eq!(touched([0.0, 0.0], [100.0, 100.0]), [0]);
+        assert_eq!(touched([150.0, 50.0], [250.0, 150.0]), [1, 2, 4, 5]);
+        assert_eq!(touched([120.0, 130.0], [120.0, 130.0]), [4]);
+    }
+
+    #[test]
+    fn a_triangle_across_a_seam_is_cut_without_losing_any_of_it() {
+        // Spans the two halves of an image cut down the middle.
+        let triangle = [
+            vertex(0.0, 0.0, 0.0, 0.0),
+            vertex(200.0, 0.0, 1.0, 0.0),
+            vertex(0.0, 100.0, 0.0, 1.0),
+        ];
+        let mut left = Vec::new();
+        let mut right = Vec::new();
+        clip_to_tile(triangle, [0.0, 0.0, 0.5, 1.0], &mut left);
+        clip_to_tile(triangle, [0.5, 0.0, 1.0, 1.0], &mut right);
+        assert!((area(&left) + area(&right) - area(&triangle)).abs() < 1e-2);
+        for vertex in left.iter().chain(&right) {
+            let SDL_FPoint { x: u, y: v } = vertex.tex_coord;
+            assert!((-1e-4..=1.0001).contains(&u) && (-1e-4..=1.0001).contains(&v));
+        }
+        // The seam sits at the tile's right edge on one side, its left on the other.
+        assert!(left.iter().any(|v| (v.tex_coord.x - 1.0).abs() < 1e-4));
+        assert!(right.iter().any(|v| v.tex_coord.x.abs() < 1e-4));
+    }
+
+    #[test]
+    fn a_repeated_texture_is_cut_at_every_repeat() {
+        // Samples the texture twice across, once down.
+        let triangle = [
+            vertex(0.0, 0.0, 0.0, 0.0),
+            vertex(200.0, 0.0, 2.0, 0.0),
+            vertex(0.0, 100.0, 0.0, 1.0),
+        ];
+        let mut repeated = Vec::new();
+        wrap(triangle, false, &mut repeated);
+        assert!((area(&repeated) - area(&triangle)).abs() < 1e-2);
+        // Past the first repeat, coordinates start again from 0.
+        for vertex in repeated.iter().filter(|v| v.position.x > 100.5) {
+            assert!((vertex.tex_coord.x - (vertex.position.x / 100.0 - 1.0)).abs() < 1e-3);
+        }
+
+        // Mirrored, the second repeat runs backwards.
+        let mut mirrored = Vec::new();
+        wrap(triangle, true, &mut mirrored);
+        let far = mirrored
+            .iter()
+            .find(|v| v.position.x > 199.0)
+            .expect("the far corner");
+        assert!(far.tex_coord.x.abs() < 1e-4);
+    }
+
+    #[test]
+    fn a_triangle_elsewhere_leaves_a_tile_alone() {
+        let triangle = [
+            vertex(0.0, 0.0, 0.0, 0.0),
+            vertex(10.0, 0.0, 0.2, 0.0),
+            vertex(0.0, 10.0, 0.0, 0.2),
+        ];
+        let mut out = Vec::new();
+        clip_to_tile(triangle, [0.5, 0.5, 1.0, 1.0], &mut out);
+        assert!(out.is_empty());
+    }
+}
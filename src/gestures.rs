@@ -0,0 +1,175 @@
+//! Gestures [`crate::State`] reads from touches on top of the first finger's
+//! primary-button stream: a long press for a secondary click, and a
+//! two-finger pan for scrolling. See [`crate::State::set_touch_gestures`].
+//!
+//! Only a touchscreen's fingers are where they touch; a trackpad reporting
+//! touches is handled as [`TouchHandling`] says instead.
+
+use egui::Pos2;
+use std::time::Duration;
+
+/// Which touch gestures are recognised, and their thresholds. `State` reads
+/// none until given some; [`Default`] is a long press and a two-finger scroll.
+///
+/// While any is on, the first finger's press reaches egui only once it is
+/// plainly a tap (it lifts), a drag (it moves past [`Self::slop`]) or neither
+/// (a long press or a second finger). Taps, double taps and drags work as
+/// before. The raw [`egui::Event::Touch`] stream is never changed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TouchGestures {
+    /// How long a finger rests before it is a secondary click; `None` for no
+    /// long press.
+    pub long_press: Option<Duration>,
+    /// How far, in points, a finger may wander and still be a tap or a long
+    /// press.
+    pub slop: f32,
+    /// Whether two fingers moving together scroll, as a wheel does.
+    pub two_finger_scroll: bool,
+}
+
+impl Default for TouchGestures {
+    fn default() -> Self {
+        Self {
+            long_press: Some(Duration::from_millis(500)),
+            slop: 8.0,
+            two_finger_scroll: true,
+        }
+    }
+}
+
+impl TouchGestures {
+    /// No gestures: every first finger presses at once, as a mouse would.
+    pub fn none() -> Self {
+        Self {
+            long_press: None,
+            slop: 0.0,
+            two_finger_scroll: false,
+        }
+    }
+
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.long_press.is_some() || self.two_finger_scroll
+    }
+
+    /// How far a finger held for `held` is into its long press, from 0 to 1.
+    pub fn long_press_progress(&self, held: Duration) -> Option<f32> {
+        let long_press = self.long_press?;
+        if long_press.is_zero() {
+            return Some(1.0);
+        }
+        Some((held.as_secs_f32() / long_press.as_secs_f32()).min(1.0))
+    }
+}
+
+/// What `State` does with the fingers of one touch device.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TouchHandling {
+    /// Each finger is where it touches the window, and the first drives egui's
+    /// pointer: a touchscreen.
+    Pointer,
+    /// Only how the fingers move counts: two together scroll, and pinch to
+    /// zoom. One alone does nothing, the OS moving the mouse with it: a
+    /// trackpad.
+    Scroll,
+    /// The device's touches reach egui not at all.
+    Ignore,
+}
+
+/// How to handle a device SDL reports as `device_type`, with `indirect` for
+/// one that is not a touchscreen. A device SDL does not know, such as one
+/// pushed by the app, is taken for a touchscreen.
+pub(crate) fn handling_for(
+    device_type: sdl2::sys::SDL_TouchDeviceType,
+    indirect: TouchHandling,
+) -> TouchHandling {
+    use sdl2::sys::SDL_TouchDeviceType::*;
+    match device_type {
+        SDL_TOUCH_DEVICE_DIRECT | SDL_TOUCH_DEVICE_INVALID => TouchHandling::Pointer,
+        SDL_TOUCH_DEVICE_INDIRECT_ABSOLUTE | SDL_TOUCH_DEVICE_INDIRECT_RELATIVE => indirect,
+    }
+}
+
+/// How far apart exactly two fingers are.
+pub(crate) fn two_finger_span(fingers: &[(i64, Pos2)]) -> Option<f32> {
+    match fingers {
+        [(_, a), (_, b)] => Some(a.distance(*b)),
+        _ => None,
+    }
+}
+
+/// The middle of exactly two fingers, which a two-finger pan moves.
+pub(crate) fn two_finger_centre(fingers: &[(i64, Pos2)]) -> Option<Pos2> {
+    match fingers {
+        [(_, a), (_, b)] => Some(a.lerp(*b, 0.5)),
+        _ => None,
+    }
+}
+
+/// Show a long press coming: a ring closing in on the finger, from a third of
+/// the way in so quick taps don't flash it.
+pub(crate) fn paint_long_press_hint(ctx: &egui::Context, pos: Pos2, progress: f32) {
+    if progress < 1.0 / 3.0 {
+        return;
+    }
+    let painter = ctx.layer_painter(egui::LayerId::new(
+        egui::Order::Debug,
+        egui::Id::new("egui_sdl2_long_press"),
+    ));
+    let color = ctx.global_style().visuals.selection.stroke.color;
+    let radius = egui::lerp(40.0..=20.0, progress);
+    painter.circle_stroke(
+        pos,
+        radius,
+        egui::Stroke::new(3.0, color.gamma_multiply(progress)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_long_press_fills_up_and_two_fingers_have_a_centre() {
+        let gestures = TouchGestures::default();
+        let progress = |ms| gestures.long_press_progress(Duration::from_millis(ms));
+        assert_eq!(progress(250), Some(0.5));
+        assert_eq!(progress(900), Some(1.0));
+        assert_eq!(
+            TouchGestures::none().long_press_progress(Duration::ZERO),
+            None
+        );
+        assert!(!TouchGestures::none().is_enabled());
+
+        let a = (1, Pos2::new(10.0, 10.0));
+        let b = (2, Pos2::new(30.0, 50.0));
+        assert_eq!(two_finger_centre(&[a, b]), Some(Pos2::new(20.0, 30.0)));
+        assert_eq!(two_finger_centre(&[a]), None);
+        assert_eq!(
+            two_finger_span(&[a, b]),
+            Some(Pos2::new(10.0, 10.0).distance(b.1))
+        );
+    }
+
+    #[test]
+    fn only_touchscreens_are_pointers() {
+        use sdl2::sys::SDL_TouchDeviceType::*;
+        let indirect = TouchHandling::Scroll;
+        assert_eq!(
+            handling_for(SDL_TOUCH_DEVICE_DIRECT, indirect),
+            TouchHandling::Pointer
+        );
+        assert_eq!(
+            handling_for(SDL_TOUCH_DEVICE_INVALID, indirect),
+            TouchHandling::Pointer
+        );
+        assert_eq!(
+            handling_for(SDL_TOUCH_DEVICE_INDIRECT_ABSOLUTE, indirect),
+            indirect
+        );
+        assert_eq!(
+            handling_for(SDL_TOUCH_DEVICE_INDIRECT_RELATIVE, TouchHandling::Ignore),
+            TouchHandling::Ignore
+        );
+    }
+}
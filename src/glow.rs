@@ -19,9 +19,61 @@
 //! 4. Paint egui output via [`EguiGlow::paint`]
 //!
 
+use glow::HasContext as _;
+
+/// Why [`EguiGlow::try_new`] could not paint with the context it was given.
+#[derive(Debug)]
+pub enum GlowError {
+    /// The context is older than the OpenGL 2.0 / ES 2.0 egui's shaders need.
+    UnsupportedVersion { version: String },
+    /// egui_glow could not build its painter: a shader that did not compile or
+    /// link, which several Mali GLES blobs manage on a context they did create,
+    /// or a buffer it could not allocate.
+    Painter(egui_glow::PainterError),
+}
+
+impl std::fmt::Display for GlowError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnsupportedVersion { version } => {
+                write!(f, "OpenGL {version} is too old for egui, which needs 2.0")
+            }
+            Self::Painter(e) => write!(f, "egui_glow painter: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for GlowError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::UnsupportedVersion { .. } => None,
+            Self::Painter(e) => Some(e),
+        }
+    }
+}
+
+/// Where [`EguiGlow`] paints instead of the window; see [`EguiGlow::set_target`].
+///
+/// The image lands the way GL stores it, bottom row first: texture coordinate
+/// `v = 1` is the top of the UI. [`EguiGlow::read_target`] turns it the right
+/// way up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GlowTarget {
+    /// A framebuffer the app built, with a colour attachment of the target size.
+    Framebuffer(glow::Framebuffer),
+    /// An RGBA texture of the target size, attached to a framebuffer
+    /// [`EguiGlow`] keeps for the purpose.
+    Texture(glow::Texture),
+}
+
 /// Integration between [`egui`] and [`glow`] for app based on [`sdl2`].
 pub struct EguiGlow {
     run_output: crate::EguiRunOutput,
+    /// Whether [`Self::paint`] and [`Self::clear`] put the GL state back.
+    preserve_gl_state: bool,
+    target: Option<GlowTarget>,
+    /// The framebuffer a [`GlowTarget::Texture`] is attached to, made on first use.
+    texture_framebuffer: Option<glow::Framebuffer>,
     pub ctx: egui::Context,
     pub state: crate::State,
     pub painter: egui_glow::Painter,
@@ -29,27 +81,50 @@ pub struct EguiGlow {
 
 impl EguiGlow {
     /// For automatic shader version detection set `shader_version` to `None`.
+    ///
+    /// Panics if the painter cannot be built; [`Self::try_new`] reports it.
     pub fn new(
         window: &sdl2::video::Window,
         glow_ctx: std::sync::Arc<glow::Context>,
         shader_version: Option<egui_glow::ShaderVersion>,
         dithering: bool,
     ) -> Self {
+        Self::try_new(window, glow_ctx, shader_version, dithering).unwrap_or_else(|err| {
+            log::error!("error occurred in initializing painter:\n{err}");
+            panic!("{err}")
+        })
+    }
+
+    /// [`Self::new`], returning an error where the driver let egui down rather
+    /// than panicking, so the caller can try another renderer.
+    pub fn try_new(
+        window: &sdl2::video::Window,
+        glow_ctx: std::sync::Arc<glow::Context>,
+        shader_version: Option<egui_glow::ShaderVersion>,
+        dithering: bool,
+    ) -> Result<Self, GlowError> {
+        let version = glow_ctx.version();
+        if version.major < 2 {
+            let es = if version.is_embedded { " ES" } else { "" };
+            return Err(GlowError::UnsupportedVersion {
+                version: format!("{}.{}{es}", version.major, version.minor),
+            });
+        }
         let painter = egui_glow::Painter::new(glow_ctx, "", shader_version, dithering)
-            .map_err(|err| {
-                log::error!("error occurred in initializing painter:\n{err}");
-            })
-            .unwrap();
+            .map_err(GlowError::Painter)?;
         let ctx = egui::Context::default();
         let state = crate::State::new(window, ctx.clone(), egui::ViewportId::ROOT);
         let run_output = crate::EguiRunOutput::default();
 
-        Self {
+        Ok(Self {
             painter,
             run_output,
+            preserve_gl_state: false,
+            target: None,
+            texture_framebuffer: None,
             state,
             ctx,
-        }
+        })
     }
 
     #[inline]
@@ -85,8 +160,55 @@ impl EguiGlow {
         self.run_output.repaint_delay
     }
 
+    /// Have [`Self::paint`] and [`Self::clear`] leave the GL state as they
+    /// found it, for an app drawing its own scene in the same context. Off by
+    /// default: the snapshot is a few dozen `glGet` calls a frame, which some
+    /// drivers answer by stalling. See [`GlState`] for what is covered.
+    pub fn set_preserve_gl_state(&mut self, preserve: bool) {
+        self.preserve_gl_state = preserve;
+    }
+
+    pub fn preserve_gl_state(&self) -> bool {
+        self.preserve_gl_state
+    }
+
+    /// Paint into `target`, `size` pixels large, instead of the window, from the
+    /// next [`Self::run`] on: egui lays out for that size (see
+    /// [`crate::State::set_surface_size`]) and the viewport covers it. A panel in
+    /// a 3D scene feeds its pointer through [`crate::ray_to_surface`] and
+    /// [`crate::State::on_surface_pointer`].
+    pub fn set_target(&mut self, target: GlowTarget, size: (u32, u32)) {
+        self.target = Some(target);
+        self.state.set_surface_size(Some(size));
+    }
+
+    /// Go back to painting the window.
+    pub fn reset_target(&mut self) {
+        self.target = None;
+        self.state.set_surface_size(None);
+    }
+
+    #[inline]
+    pub fn target(&self) -> Option<GlowTarget> {
+        self.target
+    }
+
+    /// Read back what was last painted, top row first: the target if one is
+    /// set, else the window's back buffer. For screenshots and tests; the read
+    /// waits for the GPU to finish.
+    pub fn read_target(&mut self) -> egui::ColorImage {
+        let size = self.state.get_drawable_size();
+        let previous = self.bind_target();
+        let image = self.painter.read_screen_rgba(size.into());
+        self.unbind_target(previous);
+        image
+    }
+
     /// Paint the results of the last call to [`Self::run`].
     pub fn paint(&mut self) {
+        let saved = self.capture_gl_state();
+        let previous = self.bind_target();
+        let screenshots = std::mem::take(&mut self.run_output.screenshots);
         let pixels_per_point = self.run_output.pixels_per_point;
         let (mut textures_delta, shapes) = self.run_output.take();
         let mut clipped_primitives = self.ctx.tessellate(shapes, pixels_per_point);
@@ -110,17 +232,272 @@ impl EguiGlow {
             // egui_glow 0.36 drains the deltas in place.
             &mut textures_delta,
         );
+        if !screenshots.is_empty() {
+            // The back buffer holds the frame as the window shows it.
+            let image = self.painter.read_screen_rgba(screen_size.into());
+            let image = std::sync::Arc::new(rotation.unturn_image(&image));
+            for user_data in screenshots {
+                self.state.on_screenshot(user_data, image.clone());
+            }
+        }
+        self.unbind_target(previous);
+        if let Some(saved) = saved {
+            unsafe { saved.restore(self.painter.gl()) }
+        }
     }
 
     #[inline]
-    pub fn clear(&self, color: [f32; 4]) {
+    pub fn clear(&mut self, color: [f32; 4]) {
+        let saved = self.capture_gl_state();
+        let previous = self.bind_target();
         // Physical framebuffer size, matching the viewport used in `paint`.
         let size = self.state.get_drawable_size();
         self.painter.clear(size.into(), color);
+        self.unbind_target(previous);
+        if let Some(saved) = saved {
+            unsafe { saved.restore(self.painter.gl()) }
+        }
+    }
+
+    /// Bind the target's framebuffer, if there is a target, and return the one
+    /// it replaced.
+    fn bind_target(&mut self) -> Option<Option<glow::Framebuffer>> {
+        let target = self.target?;
+        let gl = self.painter.gl().clone();
+        unsafe {
+            let previous = gl.get_parameter_framebuffer(glow::FRAMEBUFFER_BINDING);
+            match target {
+                GlowTarget::Framebuffer(framebuffer) => {
+                    gl.bind_framebuffer(glow::FRAMEBUFFER, Some(framebuffer));
+                }
+                GlowTarget::Texture(texture) => {
+                    let framebuffer = match self.texture_framebuffer {
+                        Some(framebuffer) => framebuffer,
+                        None => match gl.create_framebuffer() {
+                            Ok(framebuffer) => *self.texture_framebuffer.insert(framebuffer),
+                            Err(err) => {
+                                log::warn!("Failed to create a framebuffer for egui: {err}");
+                                return Some(previous);
+                            }
+                        },
+                    };
+                    gl.bind_framebuffer(glow::FRAMEBUFFER, Some(framebuffer));
+                    gl.framebuffer_texture_2d(
+                        glow::FRAMEBUFFER,
+                        glow::COLOR_ATTACHMENT0,
+                        glow::TEXTURE_2D,
+                        Some(texture),
+                        0,
+                    );
+                }
+            }
+            let status = gl.check_framebuffer_status(glow::FRAMEBUFFER);
+            if status != glow::FRAMEBUFFER_COMPLETE {
+                log::warn!("egui's target framebuffer is incomplete: {status:#x}");
+            }
+            Some(previous)
+        }
+    }
+
+    fn unbind_target(&self, previous: Option<Option<glow::Framebuffer>>) {
+        if let Some(previous) = previous {
+            unsafe {
+                self.painter
+                    .gl()
+                    .bind_framebuffer(glow::FRAMEBUFFER, previous)
+            }
+        }
+    }
+
+    fn capture_gl_state(&self) -> Option<GlState> {
+        self.preserve_gl_state
+            .then(|| unsafe { GlState::capture(self.painter.gl()) })
     }
 
     /// Call to release the allocated graphics resources.
     pub fn destroy(&mut self) {
+        if let Some(framebuffer) = self.texture_framebuffer.take() {
+            unsafe { self.painter.gl().delete_framebuffer(framebuffer) }
+        }
         self.painter.destroy();
     }
 }
+
+/// The GL state egui_glow changes while it paints or clears, as it was: the
+/// program, vertex array and buffers, the active unit and the textures on it
+/// and on unit 0, viewport and scissor, blending, culling, depth test, colour mask, sRGB
+/// framebuffer, clear colour and unpack alignment. The vertex array is put
+/// back wherever egui_glow uses one: GL 3 and GLES 3, and GLES 2.0 and GL 2.1
+/// with `OES_vertex_array_object` or `ARB_vertex_array_object`. Without it
+/// egui_glow sets up its attributes directly, and those are not put back.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GlState {
+    program: Option<glow::Program>,
+    /// `None` where egui_glow draws without a vertex array object.
+    vertex_array: Option<Option<glow::VertexArray>>,
+    array_buffer: Option<glow::Buffer>,
+    element_array_buffer: Option<glow::Buffer>,
+    active_texture: u32,
+    /// On the active unit, where egui_glow uploads textures before it paints.
+    active_texture_2d: Option<glow::Texture>,
+    /// On unit 0, where egui_glow paints from.
+    texture_2d: Option<glow::Texture>,
+    viewport: [i32; 4],
+    scissor_box: [i32; 4],
+    scissor_test: bool,
+    blend: bool,
+    blend_equation: [u32; 2],
+    /// Source and destination for colour, then for alpha.
+    blend_func: [u32; 4],
+    cull_face: bool,
+    depth_test: bool,
+    color_mask: [bool; 4],
+    /// `None` on GLES, which has no such switch.
+    framebuffer_srgb: Option<bool>,
+    clear_color: [f32; 4],
+    unpack_alignment: i32,
+}
+
+impl GlState {
+    /// # Safety
+    /// `gl`'s context must be current.
+    pub unsafe fn capture(gl: &glow::Context) -> Self {
+        use glow::HasContext as _;
+        let version = gl.version();
+        let vertex_arrays = uses_vertex_arrays(
+            &unsafe { gl.get_parameter_string(glow::VERSION) },
+            gl.supported_extensions(),
+        );
+        let mut viewport = [0; 4];
+        let mut scissor_box = [0; 4];
+        let mut clear_color = [0.0; 4];
+        unsafe {
+            gl.get_parameter_i32_slice(glow::VIEWPORT, &mut viewport);
+            gl.get_parameter_i32_slice(glow::SCISSOR_BOX, &mut scissor_box);
+            gl.get_parameter_f32_slice(glow::COLOR_CLEAR_VALUE, &mut clear_color);
+            let active_texture = gl.get_parameter_i32(glow::ACTIVE_TEXTURE) as u32;
+            let active_texture_2d = gl.get_parameter_texture(glow::TEXTURE_BINDING_2D);
+            gl.active_texture(glow::TEXTURE0);
+            let texture_2d = gl.get_parameter_texture(glow::TEXTURE_BINDING_2D);
+            gl.active_texture(active_texture);
+            Self {
+                program: gl.get_parameter_program(glow::CURRENT_PROGRAM),
+                vertex_array: vertex_arrays
+                    .then(|| gl.get_parameter_vertex_array(glow::VERTEX_ARRAY_BINDING)),
+                array_buffer: gl.get_parameter_buffer(glow::ARRAY_BUFFER_BINDING),
+                element_array_buffer: gl.get_parameter_buffer(glow::ELEMENT_ARRAY_BUFFER_BINDING),
+                active_texture,
+                active_texture_2d,
+                texture_2d,
+                viewport,
+                scissor_box,
+                scissor_test: gl.is_enabled(glow::SCISSOR_TEST),
+                blend: gl.is_enabled(glow::BLEND),
+                blend_equation: [
+                    gl.get_parameter_i32(glow::BLEND_EQUATION_RGB) as u32,
+                    gl.get_parameter_i32(glow::BLEND_EQUATION_ALPHA) as u32,
+                ],
+                blend_func: [
+                    gl.get_parameter_i32(glow::BLEND_SRC_RGB) as u32,
+                    gl.get_parameter_i32(glow::BLEND_DST_RGB) as u32,
+                    gl.get_parameter_i32(glow::BLEND_SRC_ALPHA) as u32,
+                    gl.get_parameter_i32(glow::BLEND_DST_ALPHA) as u32,
+                ],
+                cull_face: gl.is_enabled(glow::CULL_FACE),
+                depth_test: gl.is_enabled(glow::DEPTH_TEST),
+                color_mask: gl.get_parameter_bool_array(glow::COLOR_WRITEMASK),
+                framebuffer_srgb: (!version.is_embedded)
+                    .then(|| gl.is_enabled(glow::FRAMEBUFFER_SRGB)),
+                clear_color,
+                unpack_alignment: gl.get_parameter_i32(glow::UNPACK_ALIGNMENT),
+            }
+        }
+    }
+
+    /// # Safety
+    /// `gl`'s context must be current, and the objects captured not deleted since.
+    pub unsafe fn restore(&self, gl: &glow::Context) {
+        use glow::HasContext as _;
+        let toggle = |capability, on: bool| unsafe {
+            if on {
+                gl.enable(capability)
+            } else {
+                gl.disable(capability)
+            }
+        };
+        unsafe {
+            gl.use_program(self.program);
+            // The element buffer belongs to the vertex array, so that goes first.
+            if let Some(vertex_array) = self.vertex_array {
+                gl.bind_vertex_array(vertex_array);
+            }
+            gl.bind_buffer(glow::ARRAY_BUFFER, self.array_buffer);
+            gl.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, self.element_array_buffer);
+            gl.active_texture(glow::TEXTURE0);
+            gl.bind_texture(glow::TEXTURE_2D, self.texture_2d);
+            gl.active_texture(self.active_texture);
+            gl.bind_texture(glow::TEXTURE_2D, self.active_texture_2d);
+            let [x, y, w, h] = self.viewport;
+            gl.viewport(x, y, w, h);
+            let [x, y, w, h] = self.scissor_box;
+            gl.scissor(x, y, w, h);
+            toggle(glow::SCISSOR_TEST, self.scissor_test);
+            toggle(glow::BLEND, self.blend);
+            gl.blend_equation_separate(self.blend_equation[0], self.blend_equation[1]);
+            let [src_rgb, dst_rgb, src_alpha, dst_alpha] = self.blend_func;
+            gl.blend_func_separate(src_rgb, dst_rgb, src_alpha, dst_alpha);
+            toggle(glow::CULL_FACE, self.cull_face);
+            toggle(glow::DEPTH_TEST, self.depth_test);
+            let [r, g, b, a] = self.color_mask;
+            gl.color_mask(r, g, b, a);
+            if let Some(srgb) = self.framebuffer_srgb {
+                toggle(glow::FRAMEBUFFER_SRGB, srgb);
+            }
+            let [r, g, b, a] = self.clear_color;
+            gl.clear_color(r, g, b, a);
+            gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, self.unpack_alignment);
+        }
+    }
+}
+
+/// Whether egui_glow draws through a vertex array object on a context with
+/// this `GL_VERSION` string and these extensions, decided as its `supports_vao`
+/// does: always from GL 3, GLES 3 and WebGL 2, and before that only with the
+/// extension.
+fn uses_vertex_arrays(version: &str, extensions: &std::collections::HashSet<String>) -> bool {
+    let has = |name: &str| extensions.contains(name) || extensions.contains(&format!("GL_{name}"));
+    if let Some(at) = version.rfind("WebGL ") {
+        !version[at..].contains("1.0") || has("OES_vertex_array_object")
+    } else if version.contains("OpenGL ES ") {
+        !version.contains("2.0") || has("OES_vertex_array_object")
+    } else {
+        !version.starts_with('2') || has("ARB_vertex_array_object")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vertex_arrays_are_saved_wherever_egui_glow_uses_them() {
+        let none = std::collections::HashSet::new();
+        let with = |name: &str| std::collections::HashSet::from([name.to_string()]);
+        assert!(uses_vertex_arrays("3.3.0 NVIDIA 535.0", &none));
+        assert!(uses_vertex_arrays("OpenGL ES 3.2 Mesa 23.0", &none));
+        assert!(!uses_vertex_arrays("OpenGL ES 2.0 Mesa 23.0", &none));
+        assert!(uses_vertex_arrays(
+            "OpenGL ES 2.0 Mesa 23.0",
+            &with("GL_OES_vertex_array_object")
+        ));
+        assert!(!uses_vertex_arrays("2.1 Mesa 23.0", &none));
+        assert!(uses_vertex_arrays(
+            "2.1 Mesa 23.0",
+            &with("GL_ARB_vertex_array_object")
+        ));
+        assert!(!uses_vertex_arrays(
+            "2.1 Mesa 23.0",
+            &with("GL_OES_vertex_array_object")
+        ));
+    }
+}
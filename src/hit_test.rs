@@ -0,0 +1,187 @@
+//! A borderless window moved and resized by its own UI: egui marks the parts of
+//! the frame that act as a title bar or a resize edge, and SDL's hit test hands
+//! presses there to the window manager (see
+//! [`crate::EguiWindow::set_hit_test`]).
+
+use egui::{Pos2, Rect};
+use std::sync::Mutex;
+
+/// What a press on a marked part of the window does.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Hash)]
+pub enum HitArea {
+    /// An ordinary part of the UI, for a button on a title bar marked
+    /// [`HitArea::Draggable`] underneath it.
+    #[default]
+    Normal,
+    /// Moves the window, as a title bar does.
+    Draggable,
+    ResizeTopLeft,
+    ResizeTop,
+    ResizeTopRight,
+    ResizeRight,
+    ResizeBottomRight,
+    ResizeBottom,
+    ResizeBottomLeft,
+    ResizeLeft,
+}
+
+impl HitArea {
+    fn to_sdl(self) -> sdl2::sys::SDL_HitTestResult {
+        use sdl2::sys::SDL_HitTestResult::*;
+        match self {
+            HitArea::Normal => SDL_HITTEST_NORMAL,
+            HitArea::Draggable => SDL_HITTEST_DRAGGABLE,
+            HitArea::ResizeTopLeft => SDL_HITTEST_RESIZE_TOPLEFT,
+            HitArea::ResizeTop => SDL_HITTEST_RESIZE_TOP,
+            HitArea::ResizeTopRight => SDL_HITTEST_RESIZE_TOPRIGHT,
+            HitArea::ResizeRight => SDL_HITTEST_RESIZE_RIGHT,
+            HitArea::ResizeBottomRight => SDL_HITTEST_RESIZE_BOTTOMRIGHT,
+            HitArea::ResizeBottom => SDL_HITTEST_RESIZE_BOTTOM,
+            HitArea::ResizeBottomLeft => SDL_HITTEST_RESIZE_BOTTOMLEFT,
+            HitArea::ResizeLeft => SDL_HITTEST_RESIZE_LEFT,
+        }
+    }
+}
+
+/// Mark `rect`, in egui points, as `area` for the frame being built. Marks last
+/// a frame; one made later wins where two overlap, so mark a title bar first
+/// and the buttons on it after.
+pub fn set_hit_area(ctx: &egui::Context, rect: Rect, area: HitArea) {
+    let pass = ctx.cumulative_pass_nr();
+    ctx.data_mut(|data| {
+        let marks = data.get_temp_mut_or_default::<HitAreas>(egui::Id::NULL);
+        if marks.pass != pass {
+            marks.pass = pass;
+            marks.areas.clear();
+        }
+        marks.areas.push((rect, area));
+    });
+}
+
+/// The marks of one frame, kept in egui's memory so the UI needs nothing of
+/// ours to make them.
+#[derive(Clone, Default)]
+struct HitAreas {
+    pass: u64,
+    areas: Vec<(Rect, HitArea)>,
+}
+
+/// The marks the last frame made, in egui points.
+fn frame_hit_areas(ctx: &egui::Context) -> Vec<(Rect, HitArea)> {
+    // The pass counter has moved on by the time the frame's output is in.
+    let pass = ctx.cumulative_pass_nr().saturating_sub(1);
+    ctx.data(|data| {
+        data.get_temp::<HitAreas>(egui::Id::NULL)
+            .filter(|marks| marks.pass == pass)
+            .map(|marks| marks.areas)
+            .unwrap_or_default()
+    })
+}
+
+/// What a press at `point` does: the last mark that holds it.
+fn hit(areas: &[(Rect, HitArea)], point: Pos2) -> HitArea {
+    areas
+        .iter()
+        .rev()
+        .find(|(rect, _)| rect.contains(point))
+        .map_or(HitArea::Normal, |&(_, area)| area)
+}
+
+/// SDL's hit test on a window, answered from the marks of the last frame.
+///
+/// The marks are boxed so SDL can hold on to them; the owner unregisters with
+/// [`Self::disable`], or else outlives the window.
+pub(crate) struct HitTest {
+    /// In the window's coordinates, which are what SDL tests.
+    areas: Box<Mutex<Vec<(Rect, HitArea)>>>,
+}
+
+impl HitTest {
+    pub(crate) fn enable(window: &sdl2::video::Window) -> Result<Self, String> {
+        let areas = Box::new(Mutex::new(Vec::new()));
+        let data = &*areas as *const Mutex<_> as *mut std::ffi::c_void;
+        if unsafe { sdl2::sys::SDL_SetWindowHitTest(window.raw(), Some(hit_test), data) } != 0 {
+            return Err(format!("no hit test on this window: {}", sdl2::get_error()));
+        }
+        Ok(Self { areas })
+    }
+
+    pub(crate) fn disable(self, window: &sdl2::video::Window) {
+        unsafe { sdl2::sys::SDL_SetWindowHitTest(window.raw(), None, std::ptr::null_mut()) };
+    }
+
+    /// Take up the marks the frame egui just ran made.
+    pub(crate) fn update(
+        &self,
+        ctx: &egui::Context,
+        window: &sdl2::video::Window,
+        rotation: crate::Rotation,
+    ) {
+        let (width, height) = window.size();
+        let (pixels, _) = window.drawable_size();
+        let ppp = ctx.pixels_per_point();
+        if width == 0 || pixels == 0 || ppp <= 0.0 {
+            return;
+        }
+        // Points to pixels, and pixels to the window's own units, which differ
+        // on HiDPI.
+        let scale = ppp * width as f32 / pixels as f32;
+        let window_in_points = egui::vec2(width as f32, height as f32) / scale;
+        let areas = frame_hit_areas(ctx).into_iter().map(|(rect, area)| {
+            let rect = rotation.rect_to_window(rect, window_in_points);
+            (Rect::from_min_max(rect.min * scale, rect.max * scale), area)
+        });
+        if let Ok(mut marks) = self.areas.lock() {
+            marks.clear();
+            marks.extend(areas);
+        }
+    }
+}
+
+unsafe extern "C" fn hit_test(
+    _window: *mut sdl2::sys::SDL_Window,
+    point: *const sdl2::sys::SDL_Point,
+    data: *mut std::ffi::c_void,
+) -> sdl2::sys::SDL_HitTestResult {
+    let areas = unsafe { &*(data as *const Mutex<Vec<(Rect, HitArea)>>) };
+    let point = unsafe { *point };
+    // SDL may ask mid-update from a modal loop; a press then is an ordinary one.
+    let Ok(areas) = areas.try_lock() else {
+        return HitArea::Normal.to_sdl();
+    };
+    hit(&areas, Pos2::new(point.x as f32, point.y as f32)).to_sdl()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_later_mark_wins_over_an_earlier_one() {
+        let bar = Rect::from_min_max(Pos2::new(0.0, 0.0), Pos2::new(200.0, 30.0));
+        let close = Rect::from_min_max(Pos2::new(170.0, 0.0), Pos2::new(200.0, 30.0));
+        let areas = [(bar, HitArea::Draggable), (close, HitArea::Normal)];
+        assert_eq!(hit(&areas, Pos2::new(20.0, 10.0)), HitArea::Draggable);
+        assert_eq!(hit(&areas, Pos2::new(180.0, 10.0)), HitArea::Normal);
+        assert_eq!(hit(&areas, Pos2::new(20.0, 100.0)), HitArea::Normal);
+    }
+
+    #[test]
+    fn marks_last_the_frame_they_were_made_in() {
+        let ctx = egui::Context::default();
+        let rect = Rect::from_min_max(Pos2::ZERO, Pos2::new(10.0, 10.0));
+        let run = |mark: bool| {
+            let mut output = ctx.run_ui(Default::default(), |ui| {
+                if mark {
+                    set_hit_area(ui.ctx(), rect, HitArea::Draggable);
+                }
+            });
+            // egui 0.36 asserts that every texture delta is handled.
+            output.textures_delta.clear();
+        };
+        run(true);
+        assert_eq!(frame_hit_areas(&ctx), vec![(rect, HitArea::Draggable)]);
+        run(false);
+        assert_eq!(frame_hit_areas(&ctx), vec![]);
+    }
+}
@@ -0,0 +1,576 @@
+//! An on-screen keyboard for devices with neither a keyboard of their own nor
+//! one from the OS: [`crate::State`] shows it while a text field has focus,
+//! and it types into that field. See [`crate::State::set_virtual_keyboard`].
+//!
+//! The keyboard is painted over the UI rather than built of egui widgets: a
+//! press on a widget elsewhere would take the focus from the text field it is
+//! typing into, so `State` keeps presses on the keyboard from egui.
+
+use egui::{Event, Key, Pos2, Rect};
+
+/// One key of a [`KeyboardLayout`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VirtualKey {
+    /// Types its character, in upper case while shift is on.
+    Char(char),
+    /// Presses an egui key: Backspace, Enter, the arrows.
+    Key(Key),
+    Space,
+    /// Upper case for the next character typed.
+    Shift,
+    /// Switches to the keyboard's layout of this index.
+    Layout(usize),
+    /// Hides the keyboard by ending the text input.
+    Hide,
+}
+
+impl VirtualKey {
+    /// How wide the key is, in widths of a character key.
+    fn width(&self) -> f32 {
+        match self {
+            VirtualKey::Char(_) => 1.0,
+            VirtualKey::Space => 4.0,
+            _ => 1.5,
+        }
+    }
+
+    /// What the key reads, in glyphs egui's default fonts carry.
+    fn label(&self, layouts: &[KeyboardLayout], shift: bool) -> String {
+        match self {
+            VirtualKey::Char(c) if shift => c.to_uppercase().collect(),
+            VirtualKey::Char(c) => c.to_string(),
+            VirtualKey::Key(Key::Backspace) => "⮈".to_owned(),
+            VirtualKey::Key(Key::Enter) => "⮨".to_owned(),
+            VirtualKey::Key(Key::ArrowLeft) => "⏴".to_owned(),
+            VirtualKey::Key(Key::ArrowRight) => "⏵".to_owned(),
+            VirtualKey::Key(key) => key.symbol_or_name().to_owned(),
+            VirtualKey::Space => "space".to_owned(),
+            VirtualKey::Shift => "⮉".to_owned(),
+            VirtualKey::Layout(index) => layouts
+                .get(*index)
+                .map_or_else(String::new, |layout| layout.label.clone()),
+            VirtualKey::Hide => "⏷".to_owned(),
+        }
+    }
+}
+
+/// Rows of keys, top to bottom, and the label of the key that switches to
+/// them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyboardLayout {
+    pub label: String,
+    pub rows: Vec<Vec<VirtualKey>>,
+}
+
+impl KeyboardLayout {
+    /// Panics if a row has no keys, which navigation could not land on.
+    pub fn new(label: impl Into<String>, rows: Vec<Vec<VirtualKey>>) -> Self {
+        assert!(
+            rows.iter().all(|row| !row.is_empty()),
+            "a keyboard row needs a key"
+        );
+        Self {
+            label: label.into(),
+            rows,
+        }
+    }
+
+    /// Letters, switching to layout 1 for symbols and 2 for digits, which is
+    /// where [`VirtualKeyboard::default`] puts [`Self::symbols`] and
+    /// [`Self::numeric`].
+    pub fn qwerty() -> Self {
+        Self::new(
+            "ABC",
+            vec![
+                keys("qwertyuiop", [VirtualKey::Key(Key::Backspace)]),
+                keys("asdfghjkl", [VirtualKey::Key(Key::Enter)]),
+                [vec![VirtualKey::Shift], keys("zxcvbnm,.", [])].concat(),
+                bottom_row([VirtualKey::Layout(1), VirtualKey::Layout(2)]),
+            ],
+        )
+    }
+
+    /// Punctuation, switching back to layout 0 for letters and 2 for digits.
+    pub fn symbols() -> Self {
+        Self::new(
+            "#+=",
+            vec![
+                keys("!@#$%^&*()", [VirtualKey::Key(Key::Backspace)]),
+                keys("-_=+[]{};:", [VirtualKey::Key(Key::Enter)]),
+                keys("'\"/\\|?<>`~", []),
+                bottom_row([VirtualKey::Layout(0), VirtualKey::Layout(2)]),
+            ],
+        )
+    }
+
+    /// Digits, switching back to layout 0 for letters.
+    pub fn numeric() -> Self {
+        Self::new(
+            "123",
+            vec![
+                keys("123", [VirtualKey::Key(Key::Backspace)]),
+                keys("456", [VirtualKey::Key(Key::Enter)]),
+                keys("789", [VirtualKey::Char('-')]),
+                vec![
+                    VirtualKey::Layout(0),
+                    VirtualKey::Char('0'),
+                    VirtualKey::Char('.'),
+                    VirtualKey::Hide,
+                ],
+            ],
+        )
+    }
+}
+
+fn keys<const N: usize>(chars: &str, then: [VirtualKey; N]) -> Vec<VirtualKey> {
+    chars.chars().map(VirtualKey::Char).chain(then).collect()
+}
+
+fn bottom_row(layouts: [VirtualKey; 2]) -> Vec<VirtualKey> {
+    let [first, second] = layouts;
+    vec![
+        first,
+        second,
+        VirtualKey::Space,
+        VirtualKey::Key(Key::ArrowLeft),
+        VirtualKey::Key(Key::ArrowRight),
+        VirtualKey::Hide,
+    ]
+}
+
+/// A way the gamepad or the arrow keys move about the keyboard.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Navigation {
+    Up,
+    Down,
+    Left,
+    Right,
+    /// Press the selected key.
+    Activate,
+    /// Press this key, wherever the selection is.
+    Press(Key),
+    Hide,
+}
+
+/// The on-screen keyboard and where it last showed.
+///
+/// It comes up along the bottom of the screen egui lays out for, so it follows
+/// [`crate::Rotation`] like the rest of the UI, and moves to the top when the
+/// text cursor would be under it.
+#[derive(Clone, Debug)]
+pub struct VirtualKeyboard {
+    layouts: Vec<KeyboardLayout>,
+    layout: usize,
+    shift: bool,
+    /// The keys as last shown, in points, row by row; empty while hidden.
+    rows: Vec<Vec<(Rect, VirtualKey)>>,
+    /// The key the gamepad or arrow keys are on, as row and column.
+    selected: (usize, usize),
+    /// Whether to show the selection: only once it has been moved.
+    navigating: bool,
+    /// The key a pointer went down on, as row and column.
+    pressed: Option<(usize, usize)>,
+}
+
+impl Default for VirtualKeyboard {
+    fn default() -> Self {
+        Self::new(vec![
+            KeyboardLayout::qwerty(),
+            KeyboardLayout::symbols(),
+            KeyboardLayout::numeric(),
+        ])
+    }
+}
+
+impl VirtualKeyboard {
+    /// A keyboard of `layouts`, showing the first. Panics if there are none.
+    pub fn new(layouts: Vec<KeyboardLayout>) -> Self {
+        assert!(!layouts.is_empty(), "a keyboard needs a layout");
+        Self {
+            layouts,
+            layout: 0,
+            shift: false,
+            rows: Vec::new(),
+            selected: (0, 0),
+            navigating: false,
+            pressed: None,
+        }
+    }
+
+    #[inline]
+    pub fn layouts(&self) -> &[KeyboardLayout] {
+        &self.layouts
+    }
+
+    /// Show the layout of `index` from the next frame. Out of range does
+    /// nothing.
+    pub fn set_layout(&mut self, index: usize) {
+        if index < self.layouts.len() {
+            self.layout = index;
+            self.pressed = None;
+        }
+    }
+
+    #[inline]
+    pub fn layout(&self) -> usize {
+        self.layout
+    }
+
+    #[inline]
+    pub fn is_visible(&self) -> bool {
+        !self.rows.is_empty()
+    }
+
+    /// Where the keyboard covers the UI, in points, while it shows.
+    pub fn rect(&self) -> Option<Rect> {
+        self.rows
+            .iter()
+            .flatten()
+            .map(|(rect, _)| *rect)
+            .reduce(|a, b| a.union(b))
+    }
+
+    /// Lay the keyboard out and paint it over the frame, or hide it when no
+    /// text field has focus. The backends call this after the app's UI, so the
+    /// text field has had its say.
+    pub fn show(&mut self, ctx: &egui::Context) {
+        let Some(ime) = ctx.output(|output| output.ime) else {
+            self.rows.clear();
+            self.pressed = None;
+            self.navigating = false;
+            return;
+        };
+        let screen = ctx.content_rect();
+        // A row with no keys, which the public fields let through, takes no
+        // room and can't be selected.
+        let layout: Vec<&Vec<VirtualKey>> = self.layouts[self.layout]
+            .rows
+            .iter()
+            .filter(|row| !row.is_empty())
+            .collect();
+        let units = layout
+            .iter()
+            .map(|row| row.iter().map(VirtualKey::width).sum::<f32>())
+            .fold(0.0, f32::max);
+        if units <= 0.0 || screen.width() <= 0.0 {
+            self.rows.clear();
+            return;
+        }
+        let unit = screen.width() / units;
+        let key_height = (screen.height() * 0.45 / layout.len() as f32).min(unit * 1.2);
+        let height = key_height * layout.len() as f32;
+        // Out of the way of the text cursor.
+        let top = if ime.cursor_rect.max.y > screen.max.y - height {
+            screen.min.y
+        } else {
+            screen.max.y - height
+        };
+
+        self.rows = layout
+            .iter()
+            .enumerate()
+            .map(|(row, keys)| {
+                let width = keys.iter().map(VirtualKey::width).sum::<f32>() * unit;
+                let mut x = screen.center().x - width / 2.0;
+                let y = top + row as f32 * key_height;
+                keys.iter()
+                    .map(|key| {
+                        let min = Pos2::new(x, y);
+                        x += key.width() * unit;
+                        let rect = Rect::from_min_max(min, Pos2::new(x, y + key_height));
+                        (rect, key.clone())
+                    })
+                    .collect()
+            })
+            .collect();
+        self.selected = self.clamp(self.selected);
+        self.paint(
+            ctx,
+            Rect::from_x_y_ranges(screen.x_range(), top..=top + height),
+        );
+    }
+
+    fn paint(&self, ctx: &egui::Context, rect: Rect) {
+        let painter = ctx.layer_painter(egui::LayerId::new(
+            egui::Order::Tooltip,
+            egui::Id::new("egui_sdl2_virtual_keyboard"),
+        ));
+        let visuals = &ctx.global_style().visuals;
+        painter.rect_filled(rect, 0.0, visuals.window_fill);
+        for (row, keys) in self.rows.iter().enumerate() {
+            for (col, (rect, key)) in keys.iter().enumerate() {
+                let widget = if self.pressed == Some((row, col)) {
+                    &visuals.widgets.active
+                } else if self.navigating && self.selected == (row, col) {
+                    &visuals.widgets.hovered
+                } else {
+                    &visuals.widgets.inactive
+                };
+                let fill = if *key == VirtualKey::Shift && self.shift {
+                    visuals.selection.bg_fill
+                } else {
+                    widget.weak_bg_fill
+                };
+                let rect = rect.shrink(2.0);
+                painter.rect_filled(rect, widget.corner_radius, fill);
+                painter.text(
+                    rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    key.label(&self.layouts, self.shift),
+                    egui::FontId::proportional((rect.height() * 0.45).max(8.0)),
+                    widget.fg_stroke.color,
+                );
+            }
+        }
+    }
+
+    fn key_at(&self, pos: Pos2) -> Option<(usize, usize)> {
+        self.rows.iter().enumerate().find_map(|(row, keys)| {
+            let col = keys.iter().position(|(rect, _)| rect.contains(pos))?;
+            Some((row, col))
+        })
+    }
+
+    fn key(&self, (row, col): (usize, usize)) -> Option<VirtualKey> {
+        Some(self.rows.get(row)?.get(col)?.1.clone())
+    }
+
+    fn clamp(&self, (row, col): (usize, usize)) -> (usize, usize) {
+        let row = row.min(self.rows.len().saturating_sub(1));
+        let cols = self.rows.get(row).map_or(0, Vec::len);
+        (row, col.min(cols.saturating_sub(1)))
+    }
+
+    /// A press or release of the primary button at `pos`. Whether the keyboard
+    /// took it: a press on the keyboard, or the release of one. A release that
+    /// egui saw pressed is egui's, wherever it lands.
+    pub(crate) fn on_pointer(
+        &mut self,
+        ctx: &egui::Context,
+        pos: Pos2,
+        pressed: bool,
+        events: &mut Vec<Event>,
+    ) -> bool {
+        if pressed {
+            if !self.rect().is_some_and(|rect| rect.contains(pos)) {
+                return false;
+            }
+            self.pressed = self.key_at(pos);
+            self.navigating = false;
+            ctx.request_repaint();
+            return true;
+        }
+        let Some(down) = self.pressed.take() else {
+            return false;
+        };
+        if self.key_at(pos) == Some(down) {
+            if let Some(key) = self.key(down) {
+                self.press(ctx, key, events);
+            }
+        }
+        ctx.request_repaint();
+        true
+    }
+
+    /// Forget a press whose pointer went away, as a cancelled touch does.
+    pub(crate) fn cancel_pointer(&mut self) {
+        self.pressed = None;
+    }
+
+    /// Move the selection or press a key. Whether the keyboard took it: only
+    /// while it shows.
+    pub(crate) fn navigate(
+        &mut self,
+        ctx: &egui::Context,
+        navigation: Navigation,
+        events: &mut Vec<Event>,
+    ) -> bool {
+        if !self.is_visible() {
+            return false;
+        }
+        let (row, col) = self.selected;
+        match navigation {
+            Navigation::Left => self.selected = (row, col.saturating_sub(1)),
+            Navigation::Right => self.selected = self.clamp((row, col + 1)),
+            Navigation::Up | Navigation::Down => {
+                let to = if navigation == Navigation::Up {
+                    row.saturating_sub(1)
+                } else {
+                    (row + 1).min(self.rows.len() - 1)
+                };
+                // The key in the next row nearest below or above this one.
+                if let (Some((rect, _)), Some(keys)) = (
+                    self.rows.get(row).and_then(|keys| keys.get(col)),
+                    self.rows.get(to),
+                ) {
+                    let x = rect.center().x;
+                    let nearest = keys
+                        .iter()
+                        .map(|(rect, _)| (rect.center().x - x).abs())
+                        .enumerate()
+                        .min_by(|a, b| a.1.total_cmp(&b.1))
+                        .map_or(0, |(col, _)| col);
+                    self.selected = (to, nearest);
+                }
+            }
+            Navigation::Activate => {
+                if let Some(key) = self.key(self.selected) {
+                    self.press(ctx, key, events);
+                }
+            }
+            Navigation::Press(key) => self.press(ctx, VirtualKey::Key(key), events),
+            Navigation::Hide => self.press(ctx, VirtualKey::Hide, events),
+        }
+        self.navigating = true;
+        ctx.request_repaint();
+        true
+    }
+
+    fn press(&mut self, ctx: &egui::Context, key: VirtualKey, events: &mut Vec<Event>) {
+        match key {
+            VirtualKey::Char(c) => {
+                let text = if self.shift {
+                    c.to_uppercase().collect()
+                } else {
+                    c.to_string()
+                };
+                events.push(Event::Text(text));
+                self.shift = false;
+            }
+            VirtualKey::Space => events.push(Event::Text(" ".to_owned())),
+            VirtualKey::Key(key) => {
+                for pressed in [true, false] {
+                    events.push(Event::Key {
+                        key,
+                        physical_key: None,
+                        pressed,
+                        repeat: false,
+                        modifiers: egui::Modifiers::NONE,
+                    });
+                }
+            }
+            VirtualKey::Shift => self.shift = !self.shift,
+            VirtualKey::Layout(index) => self.set_layout(index),
+            VirtualKey::Hide => ctx.memory_mut(|memory| memory.stop_text_input()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Run a frame of a focused text field with the keyboard over it.
+    fn frame(
+        ctx: &egui::Context,
+        keyboard: &mut VirtualKeyboard,
+        text: &mut String,
+        input: egui::RawInput,
+    ) {
+        let mut output = ctx.run_ui(input, |ui| {
+            let response = ui.text_edit_singleline(text);
+            if ui.ctx().cumulative_pass_nr() == 0 {
+                response.request_focus();
+            }
+            keyboard.show(ui.ctx());
+        });
+        // egui 0.36 asserts that every texture delta is handled.
+        output.textures_delta.clear();
+    }
+
+    fn tap(ctx: &egui::Context, keyboard: &mut VirtualKeyboard, wanted: &VirtualKey) -> Vec<Event> {
+        let (rect, _) = keyboard
+            .rows
+            .iter()
+            .flatten()
+            .find(|(_, key)| key == wanted)
+            .cloned()
+            .unwrap();
+        let mut events = Vec::new();
+        assert!(keyboard.on_pointer(ctx, rect.center(), true, &mut events));
+        assert!(keyboard.on_pointer(ctx, rect.center(), false, &mut events));
+        events
+    }
+
+    #[test]
+    fn tapping_keys_types_into_the_focused_field() {
+        let ctx = egui::Context::default();
+        let mut keyboard = VirtualKeyboard::default();
+        let mut text = String::new();
+        for _ in 0..2 {
+            frame(&ctx, &mut keyboard, &mut text, Default::default());
+        }
+        assert!(keyboard.is_visible());
+
+        let mut events = tap(&ctx, &mut keyboard, &VirtualKey::Char('h'));
+        events.extend(tap(&ctx, &mut keyboard, &VirtualKey::Shift));
+        events.extend(tap(&ctx, &mut keyboard, &VirtualKey::Char('i')));
+        events.extend(tap(&ctx, &mut keyboard, &VirtualKey::Char('i')));
+        let input = egui::RawInput {
+            events,
+            ..Default::default()
+        };
+        frame(&ctx, &mut keyboard, &mut text, input);
+        assert_eq!(text, "hIi");
+
+        let events = tap(&ctx, &mut keyboard, &VirtualKey::Hide);
+        frame(&ctx, &mut keyboard, &mut text, Default::default());
+        assert!(events.is_empty());
+        frame(&ctx, &mut keyboard, &mut text, Default::default());
+        assert!(!keyboard.is_visible());
+    }
+
+    #[test]
+    fn the_keyboard_spans_a_turned_screen() {
+        let ctx = egui::Context::default();
+        let mut keyboard = VirtualKeyboard::default();
+        let mut text = String::new();
+        // A 800 x 480 panel turned a quarter: egui lays out for 480 x 800.
+        let screen = Rect::from_min_size(Pos2::ZERO, egui::vec2(480.0, 800.0));
+        for _ in 0..2 {
+            let input = egui::RawInput {
+                screen_rect: Some(screen),
+                ..Default::default()
+            };
+            frame(&ctx, &mut keyboard, &mut text, input);
+        }
+        let rect = keyboard.rect().unwrap();
+        assert!(screen.expand(0.01).contains_rect(rect), "{rect:?}");
+        assert!((rect.width() - screen.width()).abs() < 1.0);
+        assert!(
+            (rect.max.y - screen.max.y).abs() < 0.01,
+            "below the field at the top"
+        );
+        assert!(rect.height() < screen.height() / 2.0);
+    }
+
+    #[test]
+    fn a_row_without_keys_is_passed_over() {
+        assert!(std::panic::catch_unwind(|| KeyboardLayout::new("empty", vec![vec![]])).is_err());
+        // The fields are public, so such a row can still get in.
+        let gappy = KeyboardLayout {
+            label: "gappy".to_owned(),
+            rows: vec![keys("ab", []), vec![], keys("cd", [])],
+        };
+        let ctx = egui::Context::default();
+        let mut keyboard = VirtualKeyboard::new(vec![gappy]);
+        let mut text = String::new();
+        for _ in 0..2 {
+            frame(&ctx, &mut keyboard, &mut text, Default::default());
+        }
+        assert_eq!(keyboard.rows.len(), 2);
+
+        let mut events = Vec::new();
+        for navigation in [
+            Navigation::Down,
+            Navigation::Down,
+            Navigation::Up,
+            Navigation::Up,
+            Navigation::Right,
+            Navigation::Activate,
+        ] {
+            assert!(keyboard.navigate(&ctx, navigation, &mut events));
+        }
+        assert_eq!(events, vec![Event::Text("b".to_owned())]);
+    }
+}
@@ -9,6 +9,9 @@
 //! - Render with the SDL2 software renderer via [`sdl2::render::Canvas`] (`canvas-backend` feature).
 //! - Render with OpenGL via [`glow`] (`glow-backend` feature).
 //! - Render with WebGPU via [`wgpu`](https://github.com/gfx-rs/wgpu) (`wgpu-backend` feature).
+//! - An on-screen keyboard, [`VirtualKeyboard`], for devices without one.
+//! - A pointer a game controller moves, [`VirtualPointer`], with a cursor the
+//!   painters draw.
 //! - Or let [`EguiWindow`] pick: it walks a list of renderers and keeps the
 //!   first the device actually supports.
 //!
@@ -30,7 +33,9 @@
 //!    // Call `run` + `paint` each frame, over anything you drew yourself:
 //!    egui.run(|ctx: &egui::Context| {});
 //!    canvas.clear();
-//!    egui.paint(&mut canvas);
+//!    if let Err(e) = egui.paint(&mut canvas) {
+//!        eprintln!("egui: {e}"); // painted anyway, that image as a placeholder
+//!    }
 //!    canvas.present();
 //!    std::thread::sleep(std::time::Duration::from_secs_f64(1.0 / 60.0));
 //!}
@@ -43,10 +48,19 @@ pub use sdl2;
 
 #[cfg(feature = "canvas-backend")]
 pub mod canvas;
+pub mod gestures;
 #[cfg(feature = "glow-backend")]
 pub mod glow;
+#[cfg(any(
+    feature = "glow-backend",
+    feature = "canvas-backend",
+    feature = "wgpu-backend"
+))]
+pub mod hit_test;
+pub mod keyboard;
 pub mod rotation;
 pub mod state;
+pub mod virtual_pointer;
 #[cfg(feature = "wgpu-backend")]
 pub mod wgpu;
 #[cfg(any(
@@ -58,12 +72,21 @@ pub mod window;
 
 #[cfg(feature = "canvas-backend")]
 pub use canvas::EguiCanvas;
+pub use gestures::{TouchGestures, TouchHandling};
 #[cfg(feature = "glow-backend")]
 pub use glow::*;
-pub use rotation::Rotation;
+#[cfg(any(
+    feature = "glow-backend",
+    feature = "canvas-backend",
+    feature = "wgpu-backend"
+))]
+pub use hit_test::{set_hit_area, HitArea};
+pub use keyboard::{KeyboardLayout, VirtualKey, VirtualKeyboard};
+pub use rotation::{Rotation, TouchTransform};
 pub use state::*;
+pub use virtual_pointer::{PointerAction, PointerInput, Stick, VirtualPointer};
 #[cfg(feature = "wgpu-backend")]
-pub use wgpu::EguiWgpu;
+pub use wgpu::{EguiWgpu, WgpuOptions, WgpuSetupError};
 #[cfg(any(
     feature = "glow-backend",
     feature = "canvas-backend",
@@ -106,6 +129,10 @@ pub struct EguiRunOutput {
     /// fold this into their idle wait so animations and first-frame layout show
     /// without an extra input event.
     pub repaint_delay: std::time::Duration,
+
+    /// The user data of each [`egui::ViewportCommand::Screenshot`] the UI asked
+    /// for, waiting for the backend to read its next painted frame back.
+    pub screenshots: Vec<egui::UserData>,
 }
 
 impl Default for EguiRunOutput {
@@ -117,6 +144,7 @@ impl Default for EguiRunOutput {
             pixels_per_point: 1.0,
             textures_delta: Default::default(),
             repaint_delay: std::time::Duration::MAX,
+            screenshots: Vec::new(),
         }
     }
 }
@@ -163,16 +191,36 @@ impl EguiRunOutput {
         &mut self,
         ctx: &egui::Context,
         state: &mut State,
-        run_ui: impl FnMut(&mut egui::Ui),
+        mut run_ui: impl FnMut(&mut egui::Ui),
     ) {
         let raw_input = state.take_egui_input();
+        let cursor = state.software_cursor_pos();
+        let long_press = state.long_press_hint();
+        let wants_frames = state.virtual_pointer_is_active() || long_press.is_some();
+        let mut keyboard = state.virtual_keyboard_mut();
         let egui::FullOutput {
             platform_output,
             viewport_output,
             textures_delta,
             shapes,
             pixels_per_point,
-        } = ctx.run_ui(raw_input, run_ui);
+        } = ctx.run_ui(raw_input, |ui| {
+            run_ui(ui);
+            // Over the app's UI, once its text field has asked for input.
+            if let Some(keyboard) = keyboard.as_deref_mut() {
+                keyboard.show(ui.ctx());
+            }
+            // Over everything, the keyboard too.
+            if let Some((pos, progress)) = long_press {
+                crate::gestures::paint_long_press_hint(ui.ctx(), pos, progress);
+            }
+            if let Some(pos) = cursor {
+                crate::virtual_pointer::paint_cursor(ui.ctx(), pos);
+            }
+            if wants_frames {
+                ui.ctx().request_repaint();
+            }
+        });
         state.handle_platform_output(platform_output);
 
         self.shapes = shapes;
@@ -182,9 +230,15 @@ impl EguiRunOutput {
         // backends don't drop it: a sizing pass for a freshly shown anchored Area
         // reports `ZERO` here, asking for the follow-up frame that actually paints
         // it. `MAX` if egui didn't report (idle — wait on input).
-        self.repaint_delay = viewport_output
-            .get(&egui::ViewportId::ROOT)
-            .map_or(std::time::Duration::MAX, |v| v.repaint_delay);
+        let root = viewport_output.get(&egui::ViewportId::ROOT);
+        self.repaint_delay = root.map_or(std::time::Duration::MAX, |v| v.repaint_delay);
+        // A screenshot is of the frame as painted, so it waits for the backend.
+        let commands = root.into_iter().flat_map(|v| &v.commands);
+        self.screenshots
+            .extend(commands.filter_map(|command| match command {
+                egui::ViewportCommand::Screenshot(user_data) => Some(user_data.clone()),
+                _ => None,
+            }));
     }
 
     /// Take ownership of the texture updates and shapes for the current frame.
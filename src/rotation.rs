@@ -1,14 +1,20 @@
-//! Presenting the UI at a quarter turn to the window, for a panel that is not
-//! mounted the way it is read: egui lays out for the turned screen, and the
-//! backend puts that frame on the window the other way round.
+//! Presenting the UI at a quarter turn to the window, or mirrored, for a panel
+//! that is not mounted the way it is read: egui lays out for the turned screen,
+//! and the backend puts that frame on the window the other way round.
+//!
+//! A touch digitiser need not be mounted the way the panel is; a
+//! [`TouchTransform`] brings its touches into the window's frame first.
 
 use egui::{ClippedPrimitive, Pos2, Rect, Vec2};
 
-/// How far the UI is turned clockwise on its way to the window.
+/// How the UI is turned clockwise, and whether it is mirrored first, on its way
+/// to the window: each of the eight ways a rectangle can be laid on another.
 ///
 /// A quarter turn trades the screen's width and height, so egui lays out for a
-/// portrait screen on a landscape panel (and the other way round). Pointer and
-/// touch positions travel back the same way, so a tap lands where it looks.
+/// portrait screen on a landscape panel (and the other way round). A mirrored
+/// one is for a display seen in a reflection — a teleprompter, a head-up
+/// display, a rear-projection kiosk. Pointer and touch positions travel back
+/// the same way, so a tap lands where it looks.
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Hash)]
 pub enum Rotation {
     #[default]
@@ -21,36 +27,67 @@ pub enum Rotation {
     /// A quarter turn counterclockwise: the screen's top edge runs up the
     /// window's left side.
     Cw270,
+    /// Mirrored left to right.
+    Flip,
+    /// Mirrored left to right, then a quarter turn clockwise.
+    FlipCw90,
+    /// Mirrored left to right and turned upside down: mirrored top to bottom.
+    FlipCw180,
+    /// Mirrored left to right, then a quarter turn counterclockwise: the
+    /// screen's rows run down the window's columns.
+    FlipCw270,
 }
 
 impl Rotation {
-    /// In turn order, for a setting that cycles them.
-    pub const ALL: [Rotation; 4] = [
+    /// In turn order, the plain turns before the mirrored ones, for a setting
+    /// that cycles them.
+    pub const ALL: [Rotation; 8] = [
         Rotation::None,
         Rotation::Cw90,
         Rotation::Cw180,
         Rotation::Cw270,
+        Rotation::Flip,
+        Rotation::FlipCw90,
+        Rotation::FlipCw180,
+        Rotation::FlipCw270,
     ];
 
-    /// Quarter turns clockwise, 0 to 3.
+    /// `turns` quarter turns clockwise, after mirroring left to right if
+    /// `flip`. Turns wrap as in [`Self::from_quarter_turns`].
+    #[inline]
+    pub fn new(turns: i32, flip: bool) -> Self {
+        Self::ALL[turns.rem_euclid(4) as usize + if flip { 4 } else { 0 }]
+    }
+
+    /// Quarter turns clockwise, 0 to 3, after any mirroring.
     #[inline]
     pub fn quarter_turns(self) -> u8 {
         match self {
-            Rotation::None => 0,
-            Rotation::Cw90 => 1,
-            Rotation::Cw180 => 2,
-            Rotation::Cw270 => 3,
+            Rotation::None | Rotation::Flip => 0,
+            Rotation::Cw90 | Rotation::FlipCw90 => 1,
+            Rotation::Cw180 | Rotation::FlipCw180 => 2,
+            Rotation::Cw270 | Rotation::FlipCw270 => 3,
         }
     }
 
+    /// Whether the screen is mirrored left to right before it is turned.
+    #[inline]
+    pub fn is_flipped(self) -> bool {
+        matches!(
+            self,
+            Rotation::Flip | Rotation::FlipCw90 | Rotation::FlipCw180 | Rotation::FlipCw270
+        )
+    }
+
     /// Wrapping, and negative turns count counterclockwise, so callers may add
-    /// turns without normalising first.
+    /// turns without normalising first. Never mirrored.
     #[inline]
     pub fn from_quarter_turns(turns: i32) -> Self {
-        Self::ALL[turns.rem_euclid(4) as usize]
+        Self::new(turns, false)
     }
 
-    /// Clockwise degrees, as `SDL_RenderCopyEx` takes them.
+    /// Clockwise degrees, as `SDL_RenderCopyEx` takes them; it mirrors before
+    /// it turns, as this does.
     #[inline]
     pub fn degrees(self) -> f64 {
         self.quarter_turns() as f64 * 90.0
@@ -59,7 +96,7 @@ impl Rotation {
     /// Whether width and height trade places.
     #[inline]
     pub fn swaps_axes(self) -> bool {
-        matches!(self, Rotation::Cw90 | Rotation::Cw270)
+        self.quarter_turns() % 2 == 1
     }
 
     /// The screen egui lays out for, inside a window of `window`.
@@ -82,6 +119,10 @@ impl Rotation {
             Rotation::Cw90 => Pos2::new(window.x - p.y, p.x),
             Rotation::Cw180 => Pos2::new(window.x - p.x, window.y - p.y),
             Rotation::Cw270 => Pos2::new(p.y, window.y - p.x),
+            Rotation::Flip => Pos2::new(window.x - p.x, p.y),
+            Rotation::FlipCw90 => Pos2::new(window.x - p.y, window.y - p.x),
+            Rotation::FlipCw180 => Pos2::new(p.x, window.y - p.y),
+            Rotation::FlipCw270 => Pos2::new(p.y, p.x),
         }
     }
 
@@ -94,11 +135,16 @@ impl Rotation {
             Rotation::Cw90 => Pos2::new(p.y, window.x - p.x),
             Rotation::Cw180 => Pos2::new(window.x - p.x, window.y - p.y),
             Rotation::Cw270 => Pos2::new(window.y - p.y, p.x),
+            // The mirrored half undo themselves, but for the quarter turn.
+            Rotation::Flip => Pos2::new(window.x - p.x, p.y),
+            Rotation::FlipCw90 => Pos2::new(window.y - p.y, window.x - p.x),
+            Rotation::FlipCw180 => Pos2::new(p.x, window.y - p.y),
+            Rotation::FlipCw270 => Pos2::new(p.y, p.x),
         }
     }
 
-    /// A quarter turn maps an axis-aligned rect onto another one, so a clip
-    /// rect survives the trip.
+    /// A quarter turn or a mirror maps an axis-aligned rect onto another one, so
+    /// a clip rect survives the trip.
     #[inline]
     pub fn rect_to_window(self, rect: Rect, window: Vec2) -> Rect {
         if self == Rotation::None {
@@ -110,11 +156,34 @@ impl Rotation {
         )
     }
 
+    /// Take a frame read back from the window the right way up again: the
+    /// screen egui laid out, as a screenshot of it should show.
+    pub fn unturn_image(self, image: &egui::ColorImage) -> egui::ColorImage {
+        if self == Rotation::None {
+            return image.clone();
+        }
+        let [width, height] = image.size;
+        let window = Vec2::new(width as f32, height as f32);
+        let screen = self.screen_size(window);
+        let [columns, rows] = [screen.x as usize, screen.y as usize];
+        let mut pixels = Vec::with_capacity(columns * rows);
+        for y in 0..rows {
+            for x in 0..columns {
+                // Pixel centres, so the floor lands inside the source pixel.
+                let centre = Pos2::new(x as f32 + 0.5, y as f32 + 0.5);
+                let from = self.to_window(centre, window);
+                pixels.push(image.pixels[from.y as usize * width + from.x as usize]);
+            }
+        }
+        egui::ColorImage::new([columns, rows], pixels)
+    }
+
     /// Turn a tessellated frame into window space, for backends that put their
     /// geometry on screen as it comes. `window` is the window's size in points.
     ///
-    /// Paint callbacks are moved but not turned: what they draw is the app's
-    /// own, out of reach here.
+    /// Paint callbacks are moved but not turned or mirrored: what they draw is
+    /// the app's own, out of reach here. A mirror reverses the winding of every
+    /// triangle, which no backend here culls by.
     pub fn turn_primitives(self, jobs: &mut [ClippedPrimitive], window: Vec2) {
         if self == Rotation::None {
             return;
@@ -136,6 +205,40 @@ impl Rotation {
     }
 }
 
+/// How a touch digitiser is mounted against the window it covers: its own
+/// flips first, then a turn. Touches pass through it before anything else, so
+/// they line up whatever [`Rotation`] the frame is presented at.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Hash)]
+pub struct TouchTransform {
+    /// How far a touch is turned clockwise to reach the window.
+    pub rotation: Rotation,
+    /// Whether the digitiser reports left and right the wrong way round.
+    pub flip_horizontal: bool,
+    /// Whether the digitiser reports top and bottom the wrong way round.
+    pub flip_vertical: bool,
+}
+
+impl TouchTransform {
+    /// A digitiser turned by `rotation`, and not mirrored.
+    #[inline]
+    pub fn turned(rotation: Rotation) -> Self {
+        Self {
+            rotation,
+            ..Self::default()
+        }
+    }
+
+    /// Where a touch the digitiser reports at `p`, normalised to 0..=1 as SDL
+    /// gives it, falls on the window, normalised the same way.
+    #[inline]
+    pub fn to_window(self, p: Pos2) -> Pos2 {
+        let x = if self.flip_horizontal { 1.0 - p.x } else { p.x };
+        let y = if self.flip_vertical { 1.0 - p.y } else { p.y };
+        // Normalised, the digitiser is a unit square whichever way it is turned.
+        self.rotation.to_window(Pos2::new(x, y), Vec2::splat(1.0))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -168,12 +271,20 @@ mod tests {
     #[test]
     fn the_screen_lands_on_the_window_and_nowhere_else() {
         // Every turn covers the window exactly: no gap, no overhang, and the
-        // corners still go round in order.
+        // corners still go round in order — the other way round if mirrored.
         for rotation in Rotation::ALL {
             let corners = corners(rotation);
             let rect = Rect::from_points(&corners);
             assert_eq!(rect.min, Pos2::ZERO, "{rotation:?} leaves the window");
             assert_eq!(rect.max, WINDOW.to_pos2(), "{rotation:?} leaves the window");
+            // Twice the signed area, positive for clockwise with y down.
+            let area: f32 = (0..4)
+                .map(|i| {
+                    let (a, b) = (corners[i], corners[(i + 1) % 4]);
+                    a.x * b.y - b.x * a.y
+                })
+                .sum();
+            assert_eq!(area < 0.0, rotation.is_flipped(), "{rotation:?}");
         }
     }
 
@@ -184,6 +295,17 @@ mod tests {
         assert_eq!(top_left(Rotation::Cw90), Pos2::new(WINDOW.x, 0.0));
         assert_eq!(top_left(Rotation::Cw180), WINDOW.to_pos2());
         assert_eq!(top_left(Rotation::Cw270), Pos2::new(0.0, WINDOW.y));
+        assert_eq!(top_left(Rotation::Flip), Pos2::new(WINDOW.x, 0.0));
+        assert_eq!(top_left(Rotation::FlipCw90), WINDOW.to_pos2());
+        assert_eq!(top_left(Rotation::FlipCw180), Pos2::new(0.0, WINDOW.y));
+        assert_eq!(top_left(Rotation::FlipCw270), Pos2::new(0.0, 0.0));
+
+        // A mirror is told from a turn by where the top edge runs to.
+        let top_right = |rotation: Rotation| corners(rotation)[1];
+        assert_eq!(top_right(Rotation::Flip), Pos2::new(0.0, 0.0));
+        assert_eq!(top_right(Rotation::FlipCw90), Pos2::new(WINDOW.x, 0.0));
+        assert_eq!(top_right(Rotation::FlipCw180), WINDOW.to_pos2());
+        assert_eq!(top_right(Rotation::FlipCw270), Pos2::new(0.0, WINDOW.y));
     }
 
     #[test]
@@ -206,19 +328,74 @@ mod tests {
         }
     }
 
+    #[test]
+    fn a_frame_read_from_the_window_comes_back_upright() {
+        // A 3 x 2 screen with every pixel its own shade, as egui laid it out.
+        let shade = |i: usize| egui::Color32::from_gray(i as u8);
+        let screen = egui::ColorImage::new([3, 2], (0..6).map(shade).collect());
+        for rotation in Rotation::ALL {
+            // A quarter turn trades sides either way round.
+            let window_size = rotation.screen_size(Vec2::new(3.0, 2.0));
+            // Lay the screen on the window the way a backend presents it.
+            let mut window = egui::ColorImage::filled(
+                [window_size.x as usize, window_size.y as usize],
+                egui::Color32::RED,
+            );
+            for y in 0..2 {
+                for x in 0..3 {
+                    let at =
+                        rotation.to_window(Pos2::new(x as f32 + 0.5, y as f32 + 0.5), window_size);
+                    window[(at.x as usize, at.y as usize)] = screen[(x, y)];
+                }
+            }
+            assert_eq!(rotation.unturn_image(&window), screen, "{rotation:?}");
+        }
+    }
+
     #[test]
     fn turns_wrap_in_both_directions() {
         assert_eq!(Rotation::from_quarter_turns(4), Rotation::None);
         assert_eq!(Rotation::from_quarter_turns(-1), Rotation::Cw270);
         assert_eq!(Rotation::from_quarter_turns(5), Rotation::Cw90);
+        assert_eq!(Rotation::new(-1, true), Rotation::FlipCw270);
         for rotation in Rotation::ALL {
             assert_eq!(
-                Rotation::from_quarter_turns(rotation.quarter_turns() as i32),
+                Rotation::new(rotation.quarter_turns() as i32, rotation.is_flipped()),
                 rotation
             );
         }
     }
 
+    #[test]
+    fn a_touch_is_flipped_and_then_turned() {
+        let near_top_left = Pos2::new(0.25, 0.0);
+        assert_eq!(
+            TouchTransform::default().to_window(near_top_left),
+            near_top_left
+        );
+        let mirrored = TouchTransform {
+            flip_horizontal: true,
+            ..Default::default()
+        };
+        assert_eq!(mirrored.to_window(near_top_left), Pos2::new(0.75, 0.0));
+        let upside_down = TouchTransform {
+            flip_vertical: true,
+            ..Default::default()
+        };
+        assert_eq!(upside_down.to_window(near_top_left), Pos2::new(0.25, 1.0));
+        assert_eq!(
+            TouchTransform::turned(Rotation::Cw90).to_window(near_top_left),
+            Pos2::new(1.0, 0.25)
+        );
+        // Mirrored, the point is near the top right, which a quarter turn takes
+        // to the bottom right.
+        let both = TouchTransform {
+            flip_horizontal: true,
+            ..TouchTransform::turned(Rotation::Cw90)
+        };
+        assert_eq!(both.to_window(near_top_left), Pos2::new(1.0, 0.75));
+    }
+
     #[test]
     fn a_clip_rect_stays_a_rect_the_right_way_up() {
         let rect = Rect::from_min_max(Pos2::new(10.0, 20.0), Pos2::new(110.0, 60.0));
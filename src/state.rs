@@ -36,6 +36,114 @@ pub struct EventResponse {
     pub repaint: bool,
 }
 
+/// What a clipboard shortcut does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ClipboardAction {
+    Copy,
+    Cut,
+    Paste,
+}
+
+/// The keys that copy, cut and paste, looked up on every key press.
+///
+/// [`Default`] is the platform's usual set: command with C, X and V, the
+/// dedicated Copy, Cut and Paste keys, off macOS the older Ctrl+Insert and
+/// Shift+Insert, and on Windows Shift+Delete too. Elsewhere egui's text fields
+/// take Shift+Delete as a plain delete. Modifiers match exactly, so
+/// Ctrl+Shift+V is free for the app to bind elsewhere.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClipboardShortcuts {
+    bindings: Vec<(egui::KeyboardShortcut, ClipboardAction)>,
+}
+
+impl Default for ClipboardShortcuts {
+    fn default() -> Self {
+        use egui::{KeyboardShortcut as Shortcut, Modifiers};
+        let mut bindings = vec![
+            (
+                Shortcut::new(Modifiers::COMMAND, Key::C),
+                ClipboardAction::Copy,
+            ),
+            (
+                Shortcut::new(Modifiers::COMMAND, Key::X),
+                ClipboardAction::Cut,
+            ),
+            (
+                Shortcut::new(Modifiers::COMMAND, Key::V),
+                ClipboardAction::Paste,
+            ),
+            (
+                Shortcut::new(Modifiers::NONE, Key::Copy),
+                ClipboardAction::Copy,
+            ),
+            (
+                Shortcut::new(Modifiers::NONE, Key::Cut),
+                ClipboardAction::Cut,
+            ),
+            (
+                Shortcut::new(Modifiers::NONE, Key::Paste),
+                ClipboardAction::Paste,
+            ),
+        ];
+        if !cfg!(target_os = "macos") {
+            bindings.extend([
+                (
+                    Shortcut::new(Modifiers::CTRL, Key::Insert),
+                    ClipboardAction::Copy,
+                ),
+                (
+                    Shortcut::new(Modifiers::SHIFT, Key::Insert),
+                    ClipboardAction::Paste,
+                ),
+            ]);
+        }
+        if cfg!(windows) {
+            bindings.push((
+                Shortcut::new(Modifiers::SHIFT, Key::Delete),
+                ClipboardAction::Cut,
+            ));
+        }
+        Self { bindings }
+    }
+}
+
+impl ClipboardShortcuts {
+    /// No shortcuts at all, to build a table up from.
+    pub fn none() -> Self {
+        Self {
+            bindings: Vec::new(),
+        }
+    }
+
+    /// Make `shortcut` do `action`, in place of whatever it did before.
+    pub fn bind(mut self, shortcut: egui::KeyboardShortcut, action: ClipboardAction) -> Self {
+        self.bindings.retain(|(bound, _)| *bound != shortcut);
+        self.bindings.push((shortcut, action));
+        self
+    }
+
+    /// Make `shortcut` an ordinary key press again.
+    pub fn unbind(mut self, shortcut: egui::KeyboardShortcut) -> Self {
+        self.bindings.retain(|(bound, _)| *bound != shortcut);
+        self
+    }
+
+    #[inline]
+    pub fn bindings(&self) -> &[(egui::KeyboardShortcut, ClipboardAction)] {
+        &self.bindings
+    }
+
+    /// What pressing `key` with `modifiers` held does, if it is a shortcut.
+    pub fn action(&self, modifiers: egui::Modifiers, key: Key) -> Option<ClipboardAction> {
+        self.bindings
+            .iter()
+            .find(|(shortcut, _)| {
+                shortcut.logical_key == key && modifiers.matches_exact(shortcut.modifiers)
+            })
+            .map(|&(_, action)| action)
+    }
+}
+
 /// Handles the integration between egui and a sdl2 Window.
 ///
 /// Instantiate one of these per viewport/window.
@@ -49,6 +157,33 @@ pub struct State {
     /// down becomes the pointer; extra fingers only feed multi-touch gestures so
     /// they don't emit phantom clicks. Cleared on its up/cancel.
     pointer_touch_id: Option<i64>,
+    touch_gestures: crate::TouchGestures,
+    /// The pointer finger's press, held back until it is plainly a tap or a
+    /// drag while gestures are on.
+    touch_press: Option<TouchPress>,
+    /// Whether the pointer finger went to a long press or a two-finger scroll,
+    /// and is ignored until it lifts.
+    touch_spent: bool,
+    /// Every finger down, where it last was, in points.
+    touch_fingers: Vec<(i64, egui::Pos2)>,
+    touch_scrolling: bool,
+    /// Whether mouse events SDL makes up from touches are dropped, as the
+    /// touches themselves already drive the pointer.
+    ignore_touch_mouse_events: bool,
+    /// Mouse events SDL made up from a touch, held until the finger event after
+    /// them says which device it was.
+    held_touch_mouse_events: Vec<sdl2::event::Event>,
+    /// What becomes of the fingers of a device that is not a touchscreen.
+    indirect_touch: crate::TouchHandling,
+    /// Devices the app has said how to handle, by SDL touch ID.
+    touch_overrides: std::collections::HashMap<i64, crate::TouchHandling>,
+    /// Fingers down on devices handled as [`crate::TouchHandling::Scroll`],
+    /// where they last were, in points.
+    indirect_fingers: Vec<(i64, egui::Pos2)>,
+    /// How touch digitisers are mounted against the window, unless one has its
+    /// own in `touch_transforms`.
+    touch_transform: crate::TouchTransform,
+    touch_transforms: std::collections::HashMap<i64, crate::TouchTransform>,
     current_cursor: Option<CurrentCursor>,
     /// The held modifier keys. egui 0.36 takes them as a `ModifiersChanged`
     /// event rather than a `RawInput` field, so the current set lives here.
@@ -63,6 +198,22 @@ pub struct State {
     // `set_zoom_factor` after construction is reflected without waiting for a
     // resize event (otherwise the UI lays out for the wrong rect until rotation).
     drawable_size: (u32, u32),
+    /// The pixel size of an offscreen surface the UI is laid out for instead of
+    /// the window's drawable. See [`State::set_surface_size`].
+    surface_size: Option<(u32, u32)>,
+    /// Where the last drag from another app was let go, in points.
+    drop_pos: Option<egui::Pos2>,
+    /// Whether text selected in egui is offered as the X11/Wayland primary
+    /// selection, and a middle click pastes it. See
+    /// [`State::set_primary_selection`].
+    primary_selection: bool,
+    clipboard_shortcuts: ClipboardShortcuts,
+    /// The on-screen keyboard, when the app has one.
+    virtual_keyboard: Option<crate::VirtualKeyboard>,
+    /// The finger pressing a key of the on-screen keyboard, kept from egui.
+    keyboard_touch_id: Option<i64>,
+    /// The pointer a game controller or keys move, when the app has one.
+    virtual_pointer: Option<crate::virtual_pointer::VirtualPointerState>,
 }
 
 /// A file dropped onto the window. egui 0.36 takes dropped files as a trait
@@ -116,16 +267,35 @@ impl State {
             egui_input,
             pointer_pos_in_points: None,
             pointer_touch_id: None,
+            touch_gestures: crate::TouchGestures::none(),
+            touch_press: None,
+            touch_spent: false,
+            touch_fingers: Vec::new(),
+            touch_scrolling: false,
+            ignore_touch_mouse_events: true,
+            indirect_touch: crate::TouchHandling::Scroll,
+            touch_overrides: Default::default(),
+            indirect_fingers: Vec::new(),
+            held_touch_mouse_events: Vec::new(),
+            touch_transform: crate::TouchTransform::default(),
+            touch_transforms: Default::default(),
             current_cursor: None,
             modifiers: egui::Modifiers::default(),
             rotation: crate::Rotation::None,
             window_size,
             drawable_size,
+            surface_size: None,
+            drop_pos: None,
+            primary_selection: false,
+            clipboard_shortcuts: ClipboardShortcuts::default(),
+            virtual_keyboard: None,
+            keyboard_touch_id: None,
+            virtual_pointer: None,
         }
     }
 
-    /// Present the UI at a quarter turn to the window, for a panel that is not
-    /// mounted the way it is read.
+    /// Present the UI at a quarter turn to the window, or mirrored, for a panel
+    /// that is not mounted the way it is read.
     ///
     /// This is the half of the turn that egui sees: the layout rect becomes the
     /// turned screen, and pointer positions are mapped back into it. The other
@@ -146,13 +316,65 @@ impl State {
         self.window_size
     }
 
-    /// Drawable (physical pixel) size, cached and refreshed on resize.
+    /// Drawable (physical pixel) size, cached and refreshed on resize, or the
+    /// surface size while one is set.
     ///
     /// This is the size a GPU backend should use for its viewport/framebuffer:
     /// on HiDPI it differs from [`Self::get_window_size`] (logical points).
     #[inline]
     pub fn get_drawable_size(&self) -> (u32, u32) {
-        self.drawable_size
+        self.surface_size.unwrap_or(self.drawable_size)
+    }
+
+    /// Lay the UI out for an offscreen surface of `size` pixels — a panel in a
+    /// 3D scene, say — rather than the window, which resizes no longer move.
+    /// `None` goes back to the window. Pointer input for such a surface comes
+    /// from [`Self::on_surface_pointer`] and [`Self::on_surface_button`].
+    #[inline]
+    pub fn set_surface_size(&mut self, size: Option<(u32, u32)>) {
+        self.surface_size = size;
+    }
+
+    /// Move the pointer to `uv` on the surface, with `(0, 0)` its top-left and
+    /// `(1, 1)` its bottom-right corner, as [`ray_to_surface`] gives it. `None`
+    /// is the pointer leaving the surface.
+    pub fn on_surface_pointer(&mut self, uv: Option<egui::Pos2>) -> EventResponse {
+        let Some(uv) = uv else {
+            if self.pointer_pos_in_points.take().is_none() {
+                return EventResponse::default();
+            }
+            self.egui_input.events.push(egui::Event::PointerGone);
+            return EventResponse {
+                repaint: true,
+                consumed: false,
+            };
+        };
+        let (width, height) = self.get_drawable_size();
+        let pos = self.pos_in_points(uv.x * width as f32, uv.y * height as f32);
+        self.pointer_pos_in_points = Some(pos);
+        self.egui_input.events.push(egui::Event::PointerMoved(pos));
+        EventResponse {
+            repaint: true,
+            consumed: self.egui_ctx.egui_is_using_pointer(),
+        }
+    }
+
+    /// Press or release `button` where [`Self::on_surface_pointer`] last put the
+    /// pointer. Nothing happens while the pointer is off the surface.
+    pub fn on_surface_button(&mut self, button: PointerButton, pressed: bool) -> EventResponse {
+        let Some(pos) = self.pointer_pos_in_points else {
+            return EventResponse::default();
+        };
+        self.egui_input.events.push(egui::Event::PointerButton {
+            pos,
+            button,
+            pressed,
+            modifiers: self.modifiers,
+        });
+        EventResponse {
+            repaint: true,
+            consumed: self.egui_ctx.egui_wants_pointer_input(),
+        }
     }
 
     #[inline]
@@ -160,6 +382,14 @@ impl State {
         self.pointer_pos_in_points
     }
 
+    /// Where the files and text of the last drop landed, in points. egui's
+    /// dropped files carry no position, so a UI with several drop targets asks
+    /// here; egui's own pointer is moved there too.
+    #[inline]
+    pub fn drop_pos(&self) -> Option<egui::Pos2> {
+        self.drop_pos
+    }
+
     /// Cap the texture size egui lays its font atlas out for, from the painter's
     /// own limit. egui defaults to 2048, past what handheld drivers accept, and
     /// the atlas is allocated before the first frame.
@@ -168,6 +398,211 @@ impl State {
         self.egui_input.max_texture_side = max_texture_side;
     }
 
+    /// Hand egui a screenshot it asked for with
+    /// [`egui::ViewportCommand::Screenshot`], as an [`egui::Event::Screenshot`]
+    /// in the next frame's input. The backends read the frame back and call
+    /// this; `image` is the screen egui laid out, turned back if need be.
+    pub fn on_screenshot(
+        &mut self,
+        user_data: egui::UserData,
+        image: std::sync::Arc<egui::ColorImage>,
+    ) {
+        self.egui_input.events.push(egui::Event::Screenshot {
+            viewport_id: self.viewport_id,
+            user_data,
+            image,
+        });
+        self.egui_ctx.request_repaint();
+    }
+
+    /// Keep the primary selection in step with egui: text selected in a
+    /// [`egui::TextEdit`] becomes the primary selection, and a middle click in
+    /// the focused text field inserts it where it lands. Off by default; only
+    /// Linux has a primary selection, so elsewhere this does nothing.
+    #[inline]
+    pub fn set_primary_selection(&mut self, enabled: bool) {
+        self.primary_selection = enabled;
+    }
+
+    #[inline]
+    pub fn primary_selection(&self) -> bool {
+        self.primary_selection
+    }
+
+    /// Replace the keys that copy, cut and paste; see [`ClipboardShortcuts`].
+    #[inline]
+    pub fn set_clipboard_shortcuts(&mut self, shortcuts: ClipboardShortcuts) {
+        self.clipboard_shortcuts = shortcuts;
+    }
+
+    #[inline]
+    pub fn clipboard_shortcuts(&self) -> &ClipboardShortcuts {
+        &self.clipboard_shortcuts
+    }
+
+    /// Give the app an on-screen keyboard, shown while a text field has focus,
+    /// or take it away with `None`. Presses on it, touches, the arrow keys and
+    /// Return, and a game controller's d-pad and face buttons drive it while it
+    /// shows, and egui sees none of them.
+    #[inline]
+    pub fn set_virtual_keyboard(&mut self, keyboard: Option<crate::VirtualKeyboard>) {
+        self.virtual_keyboard = keyboard;
+        self.keyboard_touch_id = None;
+    }
+
+    #[inline]
+    pub fn virtual_keyboard(&self) -> Option<&crate::VirtualKeyboard> {
+        self.virtual_keyboard.as_ref()
+    }
+
+    #[inline]
+    pub fn virtual_keyboard_mut(&mut self) -> Option<&mut crate::VirtualKeyboard> {
+        self.virtual_keyboard.as_mut()
+    }
+
+    /// Let a game controller or keys move egui's pointer and click with it, as
+    /// [`crate::VirtualPointer`] binds them, or stop with `None`. Bound controls
+    /// are kept from egui; the backends draw a cursor while it was the last to
+    /// move the pointer.
+    pub fn set_virtual_pointer(&mut self, pointer: Option<crate::VirtualPointer>) {
+        self.virtual_pointer = pointer.map(crate::virtual_pointer::VirtualPointerState::new);
+    }
+
+    #[inline]
+    pub fn virtual_pointer(&self) -> Option<&crate::VirtualPointer> {
+        self.virtual_pointer
+            .as_ref()
+            .map(|pointer| &pointer.options)
+    }
+
+    /// Where to draw the virtual pointer's cursor, in points: nowhere unless it
+    /// moved the pointer last.
+    pub fn software_cursor_pos(&self) -> Option<egui::Pos2> {
+        self.virtual_pointer
+            .as_ref()
+            .filter(|pointer| pointer.visible)
+            .and(self.pointer_pos_in_points)
+    }
+
+    /// Whether a held control keeps the virtual pointer moving or scrolling, so
+    /// another frame is wanted straight away.
+    pub fn virtual_pointer_is_active(&self) -> bool {
+        self.virtual_pointer
+            .as_ref()
+            .is_some_and(|pointer| pointer.is_active())
+    }
+
+    /// Choose the touch gestures read on top of the first finger's pointer
+    /// stream; see [`crate::TouchGestures`]. None are read by default, so a
+    /// finger presses as it lands; `TouchGestures::default()` turns on a long
+    /// press and a two-finger scroll.
+    #[inline]
+    pub fn set_touch_gestures(&mut self, gestures: crate::TouchGestures) {
+        self.touch_gestures = gestures;
+    }
+
+    #[inline]
+    pub fn touch_gestures(&self) -> &crate::TouchGestures {
+        &self.touch_gestures
+    }
+
+    /// Whether to drop the mouse events SDL synthesizes from touches
+    /// (`SDL_HINT_TOUCH_MOUSE_EVENTS`, on by default), whose `which` is
+    /// `SDL_TOUCH_MOUSEID`. On by default: the touches already drive egui's
+    /// pointer, and both at once press twice for a tap. Only those made up from
+    /// a device handled as [`crate::TouchHandling::Pointer`] are dropped; the
+    /// rest still reach egui. SDL sends them just before the finger event they
+    /// come from, and they wait for it, so forward finger events too: without
+    /// one they go through with the next other event, or at
+    /// [`Self::take_egui_input`]. Turn it off to have egui see them all, with
+    /// SDL's hint turned off instead.
+    #[inline]
+    pub fn set_ignore_touch_mouse_events(&mut self, ignore: bool) {
+        self.ignore_touch_mouse_events = ignore;
+    }
+
+    #[inline]
+    pub fn ignore_touch_mouse_events(&self) -> bool {
+        self.ignore_touch_mouse_events
+    }
+
+    /// What to do with the fingers of touch devices that are not touchscreens,
+    /// such as trackpads: scroll with them by default. SDL says which devices
+    /// are touchscreens; [`Self::set_touch_device_handling`] overrides it.
+    #[inline]
+    pub fn set_indirect_touch_handling(&mut self, handling: crate::TouchHandling) {
+        self.indirect_touch = handling;
+    }
+
+    /// Handle the touch device of SDL ID `touch_id` as `handling`, whatever SDL
+    /// says it is, or as its type says again with `None`.
+    pub fn set_touch_device_handling(
+        &mut self,
+        touch_id: i64,
+        handling: Option<crate::TouchHandling>,
+    ) {
+        match handling {
+            Some(handling) => self.touch_overrides.insert(touch_id, handling),
+            None => self.touch_overrides.remove(&touch_id),
+        };
+        self.indirect_fingers.clear();
+    }
+
+    /// How the fingers of the touch device of SDL ID `touch_id` are handled.
+    pub fn touch_device_handling(&self, touch_id: i64) -> crate::TouchHandling {
+        if let Some(handling) = self.touch_overrides.get(&touch_id) {
+            return *handling;
+        }
+        let device_type = unsafe { sdl2::sys::SDL_GetTouchDeviceType(touch_id) };
+        crate::gestures::handling_for(device_type, self.indirect_touch)
+    }
+
+    /// How touch digitisers are mounted against the window, for a panel whose
+    /// digitiser is turned or mirrored differently from its display. This is
+    /// apart from [`Self::set_rotation`]: touches are brought into the window's
+    /// frame by this first, and then follow the presented UI as the mouse does.
+    #[inline]
+    pub fn set_touch_transform(&mut self, transform: crate::TouchTransform) {
+        self.touch_transform = transform;
+    }
+
+    #[inline]
+    pub fn touch_transform(&self) -> crate::TouchTransform {
+        self.touch_transform
+    }
+
+    /// How the touch device of SDL ID `touch_id` is mounted, in place of
+    /// [`Self::set_touch_transform`], or back to that with `None`.
+    pub fn set_touch_device_transform(
+        &mut self,
+        touch_id: i64,
+        transform: Option<crate::TouchTransform>,
+    ) {
+        match transform {
+            Some(transform) => self.touch_transforms.insert(touch_id, transform),
+            None => self.touch_transforms.remove(&touch_id),
+        };
+    }
+
+    /// How the touch device of SDL ID `touch_id` is taken to be mounted.
+    pub fn touch_device_transform(&self, touch_id: i64) -> crate::TouchTransform {
+        self.touch_transforms
+            .get(&touch_id)
+            .copied()
+            .unwrap_or(self.touch_transform)
+    }
+
+    /// Where a finger is resting towards a long press, in points, and how far
+    /// into it, from 0 to 1. The backends draw a hint from this, and run frames
+    /// while it lasts so the press fires on time.
+    pub fn long_press_hint(&self) -> Option<(egui::Pos2, f32)> {
+        let press = self.touch_press.as_ref()?;
+        let progress = self
+            .touch_gestures
+            .long_press_progress(press.since.elapsed())?;
+        Some((press.origin, progress))
+    }
+
     #[inline]
     pub fn set_theme(&mut self, theme: egui::Theme) {
         self.egui_input.system_theme.replace(theme);
@@ -208,9 +643,30 @@ impl State {
             }
         }
 
+        #[cfg(target_os = "linux")]
+        if self.primary_selection {
+            self.offer_primary_selection(&platform_output.events);
+        }
+
         self.set_cursor_icon(platform_output.cursor_icon);
     }
 
+    /// Make the text last selected in a text field the primary selection. As
+    /// under X, collapsing a selection leaves the primary selection alone.
+    #[cfg(target_os = "linux")]
+    fn offer_primary_selection(&self, events: &[egui::output::OutputEvent]) {
+        let selected = events.iter().rev().find_map(|event| match event {
+            egui::output::OutputEvent::TextSelectionChanged(info) => selected_text(info),
+            _ => None,
+        });
+        // SDL takes the text as a C string.
+        if let Some(text) = selected.filter(|text| !text.contains('\0')) {
+            if let Err(err) = self.clipboard.set_primary_selection_text(&text) {
+                log::warn!("Failed to set the primary selection: {}", err);
+            }
+        }
+    }
+
     /// Prepare for a new frame by extracting the accumulated input,
     ///
     /// as well as setting [the time](egui::RawInput::time)
@@ -218,6 +674,7 @@ impl State {
     /// You need to set [`egui::RawInput::viewports`] yourself though.
     #[inline]
     pub fn take_egui_input(&mut self) -> egui::RawInput {
+        self.release_touch_mouse_events();
         self.egui_input.time = Some(self.start_time.elapsed().as_secs_f64());
         // Tell egui which viewport is now active:
         self.egui_input.viewport_id = self.viewport_id;
@@ -235,7 +692,8 @@ impl State {
             .unwrap_or(1.0);
         let ppp = self.egui_ctx.zoom_factor() * native_ppp;
         if ppp > 0.0 {
-            let points = egui::vec2(self.drawable_size.0 as f32, self.drawable_size.1 as f32) / ppp;
+            let (width, height) = self.get_drawable_size();
+            let points = egui::vec2(width as f32, height as f32) / ppp;
             if points.x > 0.0 && points.y > 0.0 {
                 // The screen egui lays out for is the *turned* one: a quarter
                 // turn trades width for height.
@@ -244,10 +702,63 @@ impl State {
                     Some(egui::Rect::from_min_size(egui::Pos2::ZERO, screen));
             }
         }
+        self.move_virtual_pointer();
+        self.fire_long_press();
 
         self.egui_input.take()
     }
 
+    /// Move the pointer as far as the virtual pointer's held controls took it
+    /// since the last frame, kept on the screen egui lays out for.
+    fn move_virtual_pointer(&mut self) {
+        let Some(pointer) = &mut self.virtual_pointer else {
+            return;
+        };
+        let (motion, scroll) = pointer.tick(std::time::Instant::now());
+        if motion != egui::Vec2::ZERO {
+            pointer.visible = true;
+            let screen = self
+                .egui_input
+                .screen_rect
+                .unwrap_or_else(|| self.egui_ctx.content_rect());
+            let from = self.pointer_pos_in_points.unwrap_or(screen.center());
+            let pos = screen.clamp(from + motion);
+            self.pointer_pos_in_points = Some(pos);
+            self.egui_input.events.push(egui::Event::PointerMoved(pos));
+        }
+        if scroll != egui::Vec2::ZERO {
+            self.egui_input.events.push(egui::Event::MouseWheel {
+                unit: MouseWheelUnit::Point,
+                delta: scroll,
+                phase: egui::TouchPhase::Move,
+                modifiers: self.modifiers,
+            });
+        }
+    }
+
+    /// Turn a finger resting long enough into a secondary click where it
+    /// landed, and spend it.
+    fn fire_long_press(&mut self) {
+        let (Some(press), Some(long_press)) = (&self.touch_press, self.touch_gestures.long_press)
+        else {
+            return;
+        };
+        if press.since.elapsed() < long_press {
+            return;
+        }
+        let pos = press.origin;
+        self.touch_press = None;
+        self.touch_spent = true;
+        for pressed in [true, false] {
+            self.egui_input.events.push(egui::Event::PointerButton {
+                pos,
+                button: PointerButton::Secondary,
+                pressed,
+                modifiers: self.modifiers,
+            });
+        }
+    }
+
     /// Pixels-per-point for mapping window pixel coordinates to egui points.
     ///
     /// Built from the *cached* native pixels-per-point — the same value
@@ -276,7 +787,8 @@ impl State {
     fn pos_in_points(&self, x: f32, y: f32) -> egui::Pos2 {
         let ppp = self.cached_pixels_per_point();
         let scale = if ppp > 0.0 { ppp } else { 1.0 };
-        let window = egui::vec2(self.drawable_size.0 as f32, self.drawable_size.1 as f32) / scale;
+        let (width, height) = self.get_drawable_size();
+        let window = egui::vec2(width as f32, height as f32) / scale;
         self.rotation.from_window(egui::pos2(x, y) / scale, window)
     }
 
@@ -289,60 +801,49 @@ impl State {
         event: &sdl2::event::Event,
     ) -> EventResponse {
         use sdl2::event::Event::*;
-        match event {
-            Window { win_event, .. } => self.on_window_event(*win_event, window),
-            MouseButtonDown {
-                mouse_btn, x, y, ..
-            } => self.on_mouse_button_event(*mouse_btn, true, *x, *y),
-            MouseButtonUp {
-                mouse_btn, x, y, ..
-            } => self.on_mouse_button_event(*mouse_btn, false, *x, *y),
-            MouseMotion { x, y, .. } => {
-                let pos = self.pos_in_points(*x as f32, *y as f32);
-                self.pointer_pos_in_points = Some(pos);
-                self.egui_input.events.push(egui::Event::PointerMoved(pos));
-                EventResponse {
-                    repaint: true,
-                    consumed: self.egui_ctx.egui_is_using_pointer(),
-                }
+        if self.ignore_touch_mouse_events && event.is_touch() {
+            // SDL sends these just before the touch they come from, which says
+            // whether they are needed. Reported as that touch is.
+            self.held_touch_mouse_events.push(event.clone());
+            return EventResponse {
+                repaint: false,
+                consumed: self.egui_ctx.egui_wants_pointer_input(),
+            };
+        }
+        if let FingerDown { touch_id, .. }
+        | FingerMotion { touch_id, .. }
+        | FingerUp { touch_id, .. } = event
+        {
+            if self.touch_device_handling(*touch_id) == crate::TouchHandling::Pointer {
+                self.held_touch_mouse_events.clear();
             }
-            MouseWheel { x, y, .. } => {
-                let dx = *x as f32;
-                let dy = *y as f32;
+        }
+        // Anything else means the touch they came from is not coming.
+        self.release_touch_mouse_events();
+        self.handle_event(window, event)
+    }
 
-                if self.modifiers.command {
-                    // zoom
-                    let delta = (dy / 125.0).exp();
-                    self.egui_input.events.push(egui::Event::Zoom(delta));
-                } else if self.modifiers.shift {
-                    // horizontal scroll
-                    self.egui_input.events.push(egui::Event::MouseWheel {
-                        unit: MouseWheelUnit::Line,
-                        delta: egui::vec2(dx + dy, 0.0),
-                        phase: egui::TouchPhase::Move,
-                        modifiers: self.modifiers,
-                    });
-                } else {
-                    // regular scroll
-                    self.egui_input.events.push(egui::Event::MouseWheel {
-                        unit: MouseWheelUnit::Line,
-                        delta: egui::vec2(dx, dy),
-                        phase: egui::TouchPhase::Move,
-                        modifiers: self.modifiers,
-                    });
-                }
-                EventResponse {
-                    repaint: true,
-                    consumed: self.egui_ctx.egui_wants_pointer_input(),
-                }
-            }
+    fn handle_event(
+        &mut self,
+        window: &sdl2::video::Window,
+        event: &sdl2::event::Event,
+    ) -> EventResponse {
+        use sdl2::event::Event::*;
+        match event {
+            Window { win_event, .. } => self.on_window_event(*win_event, window),
+            MouseButtonDown { .. }
+            | MouseButtonUp { .. }
+            | MouseMotion { .. }
+            | MouseWheel { .. } => self.on_mouse_event(event),
             KeyUp {
                 keycode: Some(kc),
                 scancode: Some(sc),
                 keymod,
                 repeat,
                 ..
-            } => self.on_keyboard_event(*kc, *sc, *keymod, false, *repeat),
+            } => self
+                .on_virtual_pointer(crate::PointerInput::Key(*kc), false)
+                .unwrap_or_else(|| self.on_keyboard_event(*kc, *sc, *keymod, false, *repeat)),
             KeyDown {
                 keycode: Some(kc),
                 scancode: Some(sc),
@@ -350,19 +851,52 @@ impl State {
                 repeat,
                 ..
             } => {
-                let resp = self.on_keyboard_event(*kc, *sc, *keymod, true, *repeat);
-
-                if self.modifiers.command && *kc == Keycode::C {
-                    self.egui_input.events.push(egui::Event::Copy);
-                } else if self.modifiers.command && *kc == Keycode::X {
-                    self.egui_input.events.push(egui::Event::Cut);
-                } else if self.modifiers.command && *kc == Keycode::V {
-                    if let Ok(contents) = self.clipboard.clipboard_text() {
-                        self.egui_input.events.push(egui::Event::Text(contents));
+                if let Some(navigation) = keycode_navigation(*kc) {
+                    if self.on_keyboard_navigation(navigation) {
+                        return EventResponse {
+                            repaint: true,
+                            consumed: true,
+                        };
                     }
                 }
+                // A held key repeats, but the pointer keeps going of itself.
+                if *repeat
+                    && self.virtual_pointer().is_some_and(|pointer| {
+                        pointer.action(crate::PointerInput::Key(*kc)).is_some()
+                    })
+                {
+                    return EventResponse {
+                        repaint: false,
+                        consumed: true,
+                    };
+                }
+                if let Some(resp) = self.on_virtual_pointer(crate::PointerInput::Key(*kc), true) {
+                    return resp;
+                }
 
-                resp
+                // The clipboard event goes first: a text field that reads the
+                // key too may already have changed the selection it acts on.
+                let action = into_egui_key(*kc).and_then(|key| {
+                    self.clipboard_shortcuts
+                        .action(into_egui_modifiers(*keymod), key)
+                });
+                match action {
+                    Some(ClipboardAction::Copy) => self.egui_input.events.push(egui::Event::Copy),
+                    Some(ClipboardAction::Cut) => self.egui_input.events.push(egui::Event::Cut),
+                    Some(ClipboardAction::Paste) => {
+                        if let Some(text) = self
+                            .clipboard
+                            .clipboard_text()
+                            .ok()
+                            .and_then(sanitize_paste)
+                        {
+                            self.egui_input.events.push(egui::Event::Paste(text));
+                        }
+                    }
+                    None => {}
+                }
+
+                self.on_keyboard_event(*kc, *sc, *keymod, true, *repeat)
             }
             TextInput { text, .. } => {
                 let mut resp = EventResponse {
@@ -386,7 +920,20 @@ impl State {
 
                 resp
             }
+            // A drag from another app is one session from begin to complete,
+            // with a file or text event per item dropped in between.
+            DropBegin { .. } => {
+                // SDL names the files only as they land, so egui hears of one it
+                // cannot name yet: enough for its "hovering files" feedback.
+                self.egui_input.hovered_files = vec![egui::HoveredFile::default()];
+                self.on_drop_moved(window);
+                EventResponse {
+                    repaint: true,
+                    consumed: false,
+                }
+            }
             DropFile { filename, .. } => {
+                self.on_drop_moved(window);
                 self.egui_input
                     .dropped_files
                     .push(std::sync::Arc::new(SdlDroppedFile(
@@ -397,6 +944,23 @@ impl State {
                     consumed: false,
                 }
             }
+            // egui has no text drop; the text goes where typing would.
+            DropText { filename: text, .. } => {
+                self.on_drop_moved(window);
+                self.egui_input.events.push(egui::Event::Text(text.clone()));
+                EventResponse {
+                    repaint: true,
+                    consumed: self.egui_ctx.egui_wants_keyboard_input(),
+                }
+            }
+            DropComplete { .. } => {
+                self.on_drop_moved(window);
+                self.egui_input.hovered_files.clear();
+                EventResponse {
+                    repaint: true,
+                    consumed: false,
+                }
+            }
             FingerDown {
                 touch_id,
                 finger_id,
@@ -442,12 +1006,157 @@ impl State {
                 y: *y,
                 pressure: *pressure,
             }),
+            ControllerButtonDown { button, .. } => {
+                if controller_navigation(*button)
+                    .is_some_and(|navigation| self.on_keyboard_navigation(navigation))
+                {
+                    return EventResponse {
+                        repaint: true,
+                        consumed: true,
+                    };
+                }
+                self.on_virtual_pointer(crate::PointerInput::Button(*button), true)
+                    .unwrap_or_default()
+            }
+            ControllerButtonUp { button, .. } => self
+                .on_virtual_pointer(crate::PointerInput::Button(*button), false)
+                .unwrap_or_default(),
+            ControllerAxisMotion { axis, value, .. } => {
+                let consumed = self
+                    .virtual_pointer
+                    .as_mut()
+                    .is_some_and(|pointer| pointer.on_axis(*axis, *value));
+                EventResponse {
+                    repaint: consumed,
+                    consumed,
+                }
+            }
             _ => EventResponse::default(),
         }
     }
 
+    /// A mouse button, motion or wheel event; anything else is not one.
+    fn on_mouse_event(&mut self, event: &sdl2::event::Event) -> EventResponse {
+        use sdl2::event::Event::*;
+        match event {
+            MouseButtonDown {
+                mouse_btn, x, y, ..
+            } => self.on_mouse_button_event(*mouse_btn, true, *x, *y),
+            MouseButtonUp {
+                mouse_btn, x, y, ..
+            } => self.on_mouse_button_event(*mouse_btn, false, *x, *y),
+            MouseMotion { x, y, .. } => {
+                if let Some(pointer) = &mut self.virtual_pointer {
+                    pointer.visible = false;
+                }
+                let pos = self.pos_in_points(*x as f32, *y as f32);
+                self.pointer_pos_in_points = Some(pos);
+                self.egui_input.events.push(egui::Event::PointerMoved(pos));
+                EventResponse {
+                    repaint: true,
+                    consumed: self.egui_ctx.egui_is_using_pointer(),
+                }
+            }
+            MouseWheel { x, y, .. } => {
+                let dx = *x as f32;
+                let dy = *y as f32;
+
+                if self.modifiers.command {
+                    // zoom
+                    let delta = (dy / 125.0).exp();
+                    self.egui_input.events.push(egui::Event::Zoom(delta));
+                } else if self.modifiers.shift {
+                    // horizontal scroll
+                    self.egui_input.events.push(egui::Event::MouseWheel {
+                        unit: MouseWheelUnit::Line,
+                        delta: egui::vec2(dx + dy, 0.0),
+                        phase: egui::TouchPhase::Move,
+                        modifiers: self.modifiers,
+                    });
+                } else {
+                    // regular scroll
+                    self.egui_input.events.push(egui::Event::MouseWheel {
+                        unit: MouseWheelUnit::Line,
+                        delta: egui::vec2(dx, dy),
+                        phase: egui::TouchPhase::Move,
+                        modifiers: self.modifiers,
+                    });
+                }
+                EventResponse {
+                    repaint: true,
+                    consumed: self.egui_ctx.egui_wants_pointer_input(),
+                }
+            }
+            _ => EventResponse::default(),
+        }
+    }
+
+    /// Pass on the mouse events SDL made up from touches that no finger event
+    /// has come to claim: the app may not forward finger events at all.
+    fn release_touch_mouse_events(&mut self) {
+        for held in std::mem::take(&mut self.held_touch_mouse_events) {
+            let _ = self.on_mouse_event(&held);
+        }
+    }
+
+    /// Hand a press or release of the primary button to the on-screen
+    /// keyboard. Whether it took it.
+    fn on_keyboard_pointer(&mut self, pos: egui::Pos2, pressed: bool) -> bool {
+        let Some(keyboard) = &mut self.virtual_keyboard else {
+            return false;
+        };
+        keyboard.on_pointer(&self.egui_ctx, pos, pressed, &mut self.egui_input.events)
+    }
+
+    /// A control the virtual pointer may be bound to going down or up; `None`
+    /// if it is not bound, for egui to have.
+    fn on_virtual_pointer(
+        &mut self,
+        input: crate::PointerInput,
+        pressed: bool,
+    ) -> Option<EventResponse> {
+        let pointer = self.virtual_pointer.as_mut()?;
+        let action = pointer.on_input(input, pressed)?;
+        pointer.visible = true;
+        let button = match action {
+            crate::PointerAction::Primary => PointerButton::Primary,
+            crate::PointerAction::Secondary => PointerButton::Secondary,
+            _ => {
+                return Some(EventResponse {
+                    repaint: true,
+                    consumed: true,
+                })
+            }
+        };
+        let pos = self
+            .pointer_pos_in_points
+            .unwrap_or_else(|| self.egui_ctx.content_rect().center());
+        self.pointer_pos_in_points = Some(pos);
+        // The control was the pointer's, whatever egui makes of the click.
+        Some(EventResponse {
+            consumed: true,
+            ..self.on_pointer_button(pos, button, pressed)
+        })
+    }
+
+    fn on_keyboard_navigation(&mut self, navigation: crate::keyboard::Navigation) -> bool {
+        let Some(keyboard) = &mut self.virtual_keyboard else {
+            return false;
+        };
+        keyboard.navigate(&self.egui_ctx, navigation, &mut self.egui_input.events)
+    }
+
     #[inline]
-    fn on_touch(&mut self, info: TouchInfo) -> EventResponse {
+    fn on_touch(&mut self, mut info: TouchInfo) -> EventResponse {
+        let on_window = self
+            .touch_device_transform(info.touch_id)
+            .to_window(egui::pos2(info.x, info.y));
+        (info.x, info.y) = (on_window.x, on_window.y);
+        match self.touch_device_handling(info.touch_id) {
+            crate::TouchHandling::Pointer => {}
+            crate::TouchHandling::Scroll => return self.on_indirect_touch(info),
+            crate::TouchHandling::Ignore => return EventResponse::default(),
+        }
         let consumed = match info.phase {
             egui::TouchPhase::Start | egui::TouchPhase::End | egui::TouchPhase::Cancel => {
                 self.egui_ctx.egui_wants_pointer_input()
@@ -466,6 +1175,46 @@ impl State {
         let pixel_x = info.x * win_w as f32;
         let pixel_y = info.y * win_h as f32;
         let pos = self.pos_in_points(pixel_x, pixel_y);
+        if let Some(pointer) = &mut self.virtual_pointer {
+            pointer.visible = false;
+        }
+
+        // A finger on the on-screen keyboard is the keyboard's alone, from the
+        // moment it lands to when it lifts.
+        let on_keyboard = self.keyboard_touch_id == Some(info.finger_id);
+        match info.phase {
+            egui::TouchPhase::Start
+                if self.pointer_touch_id.is_none()
+                    && self.keyboard_touch_id.is_none()
+                    && self.on_keyboard_pointer(pos, true) =>
+            {
+                self.keyboard_touch_id = Some(info.finger_id);
+                return EventResponse {
+                    repaint: true,
+                    consumed: true,
+                };
+            }
+            egui::TouchPhase::Move if on_keyboard => {
+                return EventResponse {
+                    repaint: false,
+                    consumed: true,
+                };
+            }
+            egui::TouchPhase::End | egui::TouchPhase::Cancel if on_keyboard => {
+                self.keyboard_touch_id = None;
+                if info.phase == egui::TouchPhase::End {
+                    self.on_keyboard_pointer(pos, false);
+                } else if let Some(keyboard) = &mut self.virtual_keyboard {
+                    keyboard.cancel_pointer();
+                }
+                return EventResponse {
+                    repaint: true,
+                    consumed: true,
+                };
+            }
+            _ => {}
+        }
+
         self.egui_input.events.push(egui::Event::Touch {
             device_id: egui::TouchDeviceId(info.touch_id as u64),
             id: egui::TouchId::from(info.finger_id as u64),
@@ -474,34 +1223,74 @@ impl State {
             force: Some(info.pressure),
         });
 
+        let centre_before = crate::gestures::two_finger_centre(&self.touch_fingers);
+        match info.phase {
+            egui::TouchPhase::Start => self.touch_fingers.push((info.finger_id, pos)),
+            egui::TouchPhase::Move => {
+                if let Some(finger) = self
+                    .touch_fingers
+                    .iter_mut()
+                    .find(|(id, _)| *id == info.finger_id)
+                {
+                    finger.1 = pos;
+                }
+            }
+            egui::TouchPhase::End | egui::TouchPhase::Cancel => {
+                self.touch_fingers.retain(|(id, _)| *id != info.finger_id);
+            }
+        }
+
         // egui's widget layer reacts to pointer events, not raw touch events, so
         // synthesize a primary-button pointer stream from the first finger (the
         // same thing egui-winit does). Without this, taps never click buttons on
         // platforms where the windowing layer doesn't synthesize mouse events from
         // touch (e.g. Android with SDL_TOUCH_MOUSE_EVENTS off). Extra fingers are
         // left to the multi-touch event above so they don't emit phantom presses.
+        let is_pointer = self.pointer_touch_id == Some(info.finger_id);
         match info.phase {
             egui::TouchPhase::Start if self.pointer_touch_id.is_none() => {
                 self.pointer_touch_id = Some(info.finger_id);
                 self.pointer_pos_in_points = Some(pos);
                 // Move to the press point first so egui has a current pointer pos.
                 self.egui_input.events.push(egui::Event::PointerMoved(pos));
-                self.egui_input.events.push(egui::Event::PointerButton {
-                    pos,
-                    button: egui::PointerButton::Primary,
-                    pressed: true,
-                    modifiers: self.modifiers,
-                });
+                if self.touch_gestures.is_enabled() {
+                    self.touch_press = Some(TouchPress {
+                        origin: pos,
+                        since: std::time::Instant::now(),
+                    });
+                } else {
+                    self.push_touch_press(pos);
+                }
+            }
+            // A second finger before the first has done anything: a scroll.
+            egui::TouchPhase::Start
+                if self.touch_gestures.two_finger_scroll
+                    && self.touch_press.is_some()
+                    && self.touch_fingers.len() == 2 =>
+            {
+                self.touch_press = None;
+                self.touch_spent = true;
+                self.touch_scrolling = true;
             }
-            egui::TouchPhase::Move if self.pointer_touch_id == Some(info.finger_id) => {
+            egui::TouchPhase::Move if is_pointer && !self.touch_spent => {
+                // Past the slop a held-back press is a drag, from where it began.
+                if let Some(press) = &self.touch_press {
+                    if press.origin.distance(pos) > self.touch_gestures.slop {
+                        let origin = press.origin;
+                        self.touch_press = None;
+                        self.push_touch_press(origin);
+                    }
+                }
                 self.pointer_pos_in_points = Some(pos);
                 self.egui_input.events.push(egui::Event::PointerMoved(pos));
             }
-            egui::TouchPhase::End | egui::TouchPhase::Cancel
-                if self.pointer_touch_id == Some(info.finger_id) =>
-            {
+            egui::TouchPhase::End | egui::TouchPhase::Cancel if is_pointer => {
                 self.pointer_touch_id = None;
-                if info.phase == egui::TouchPhase::End {
+                if info.phase == egui::TouchPhase::End && !self.touch_spent {
+                    // A tap: the press held back and the release together.
+                    if let Some(press) = self.touch_press.take() {
+                        self.push_touch_press(press.origin);
+                    }
                     self.egui_input.events.push(egui::Event::PointerButton {
                         pos,
                         button: egui::PointerButton::Primary,
@@ -509,6 +1298,8 @@ impl State {
                         modifiers: self.modifiers,
                     });
                 }
+                self.touch_press = None;
+                self.touch_spent = false;
                 // A touch pointer has no hover position once lifted; tell egui it's
                 // gone so the next press starts a fresh interaction.
                 self.egui_input.events.push(egui::Event::PointerGone);
@@ -517,12 +1308,93 @@ impl State {
             _ => {}
         }
 
+        if self.touch_scrolling {
+            let centre_after = crate::gestures::two_finger_centre(&self.touch_fingers);
+            match (centre_before, centre_after) {
+                (Some(before), Some(after)) if info.phase == egui::TouchPhase::Move => {
+                    // Content follows the fingers, as a wheel turned towards
+                    // them moves it.
+                    self.egui_input.events.push(egui::Event::MouseWheel {
+                        unit: MouseWheelUnit::Point,
+                        delta: after - before,
+                        phase: egui::TouchPhase::Move,
+                        modifiers: self.modifiers,
+                    });
+                }
+                (_, None) => self.touch_scrolling = false,
+                _ => {}
+            }
+        }
+
         EventResponse {
             repaint: true,
             consumed,
         }
     }
 
+    /// A finger on a trackpad or the like: its coordinates are the device's,
+    /// not the window's, so only how two fingers move together counts. They
+    /// move in the reader's frame, so no rotation applies.
+    fn on_indirect_touch(&mut self, info: TouchInfo) -> EventResponse {
+        let ppp = self.cached_pixels_per_point();
+        let scale = if ppp > 0.0 { ppp } else { 1.0 };
+        let (width, height) = self.window_size;
+        let pos = egui::pos2(info.x * width as f32, info.y * height as f32) / scale;
+
+        let centre_before = crate::gestures::two_finger_centre(&self.indirect_fingers);
+        let span_before = crate::gestures::two_finger_span(&self.indirect_fingers);
+        match info.phase {
+            egui::TouchPhase::Start => self.indirect_fingers.push((info.finger_id, pos)),
+            egui::TouchPhase::Move => {
+                if let Some(finger) = self
+                    .indirect_fingers
+                    .iter_mut()
+                    .find(|(id, _)| *id == info.finger_id)
+                {
+                    finger.1 = pos;
+                }
+            }
+            egui::TouchPhase::End | egui::TouchPhase::Cancel => {
+                self.indirect_fingers
+                    .retain(|(id, _)| *id != info.finger_id);
+            }
+        }
+        if info.phase != egui::TouchPhase::Move {
+            return EventResponse::default();
+        }
+
+        let centre_after = crate::gestures::two_finger_centre(&self.indirect_fingers);
+        let span_after = crate::gestures::two_finger_span(&self.indirect_fingers);
+        if let (Some(before), Some(after)) = (centre_before, centre_after) {
+            self.egui_input.events.push(egui::Event::MouseWheel {
+                unit: MouseWheelUnit::Point,
+                delta: after - before,
+                phase: egui::TouchPhase::Move,
+                modifiers: self.modifiers,
+            });
+        }
+        if let (Some(before), Some(after)) = (span_before, span_after) {
+            if before > 0.0 && after > 0.0 && before != after {
+                self.egui_input
+                    .events
+                    .push(egui::Event::Zoom(after / before));
+            }
+        }
+        EventResponse {
+            repaint: true,
+            consumed: self.egui_ctx.egui_wants_pointer_input(),
+        }
+    }
+
+    fn push_touch_press(&mut self, pos: egui::Pos2) {
+        self.egui_input.events.push(egui::Event::PointerButton {
+            pos,
+            button: egui::PointerButton::Primary,
+            pressed: true,
+            modifiers: self.modifiers,
+        });
+    }
+
     fn on_window_event(&mut self, event: WindowEvent, window: &Window) -> EventResponse {
         match event {
             WindowEvent::Minimized
@@ -580,6 +1452,26 @@ impl State {
         }
     }
 
+    /// Follow a drag from another app, which SDL sends no motion for: the
+    /// pointer is read off the desktop, or else the last known position stands.
+    fn on_drop_moved(&mut self, window: &Window) {
+        let (mut x, mut y) = (0, 0);
+        let _ = unsafe { sdl2::sys::SDL_GetGlobalMouseState(&mut x, &mut y) };
+        let (left, top) = window.position();
+        let (width, height) = window.size();
+        let (x, y) = (x - left, y - top);
+        let pos = if (0..width as i32).contains(&x) && (0..height as i32).contains(&y) {
+            Some(self.pos_in_points(x as f32, y as f32))
+        } else {
+            self.pointer_pos_in_points
+        };
+        if let Some(pos) = pos {
+            self.drop_pos = Some(pos);
+            self.pointer_pos_in_points = Some(pos);
+            self.egui_input.events.push(egui::Event::PointerMoved(pos));
+        }
+    }
+
     fn on_mouse_button_event(
         &mut self,
         button: MouseButton,
@@ -593,18 +1485,70 @@ impl State {
 
         let pos = self.pos_in_points(x as f32, y as f32);
         self.pointer_pos_in_points = Some(pos);
+        self.on_pointer_button(pos, button, pressed)
+    }
+
+    /// A button going down or up at `pos`, in points, from a mouse or the
+    /// virtual pointer.
+    fn on_pointer_button(
+        &mut self,
+        pos: egui::Pos2,
+        button: PointerButton,
+        pressed: bool,
+    ) -> EventResponse {
+        if button == PointerButton::Primary && self.on_keyboard_pointer(pos, pressed) {
+            return EventResponse {
+                repaint: true,
+                consumed: true,
+            };
+        }
         self.egui_input.events.push(egui::Event::PointerButton {
             pos,
             button,
             pressed,
             modifiers: self.modifiers,
         });
+        #[cfg(target_os = "linux")]
+        if self.primary_selection && pressed && button == PointerButton::Middle {
+            self.paste_primary_selection(pos);
+        }
         EventResponse {
             repaint: true,
             consumed: self.egui_ctx.egui_wants_pointer_input(),
         }
     }
 
+    /// Insert the primary selection at `pos`, if that is in the focused text
+    /// field. A primary click there first moves the text cursor to `pos`.
+    #[cfg(target_os = "linux")]
+    fn paste_primary_selection(&mut self, pos: egui::Pos2) {
+        let in_focused_field = self.egui_ctx.egui_wants_keyboard_input()
+            && self
+                .egui_ctx
+                .memory(|memory| memory.focused())
+                .and_then(|id| self.egui_ctx.read_response(id))
+                .is_some_and(|response| response.interact_rect.contains(pos));
+        if !in_focused_field {
+            return;
+        }
+        let text = self
+            .clipboard
+            .primary_selection_text()
+            .ok()
+            .and_then(sanitize_paste);
+        for pressed in [true, false] {
+            self.egui_input.events.push(egui::Event::PointerButton {
+                pos,
+                button: PointerButton::Primary,
+                pressed,
+                modifiers: egui::Modifiers::NONE,
+            });
+        }
+        if let Some(text) = text {
+            self.egui_input.events.push(egui::Event::Paste(text));
+        }
+    }
+
     fn on_keyboard_event(
         &mut self,
         keycode: Keycode,
@@ -692,6 +1636,48 @@ impl State {
     }
 }
 
+/// Where a ray from `origin` along `direction` meets a flat panel, as the `uv`
+/// [`State::on_surface_pointer`] takes. The panel is the parallelogram with its
+/// top-left corner at `corner` and its top and left edges along `right` and
+/// `down`, all in the same world space as the ray. `None` if the ray misses it,
+/// runs parallel to it or points away.
+pub fn ray_to_surface(
+    origin: [f32; 3],
+    direction: [f32; 3],
+    corner: [f32; 3],
+    right: [f32; 3],
+    down: [f32; 3],
+) -> Option<egui::Pos2> {
+    let sub = |a: [f32; 3], b: [f32; 3]| [a[0] - b[0], a[1] - b[1], a[2] - b[2]];
+    let dot = |a: [f32; 3], b: [f32; 3]| a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+    let cross = |a: [f32; 3], b: [f32; 3]| {
+        [
+            a[1] * b[2] - a[2] * b[1],
+            a[2] * b[0] - a[0] * b[2],
+            a[0] * b[1] - a[1] * b[0],
+        ]
+    };
+    let normal = cross(right, down);
+    let area = dot(normal, normal);
+    let facing = dot(direction, normal);
+    if area <= f32::EPSILON || facing.abs() <= f32::EPSILON {
+        return None;
+    }
+    let distance = dot(sub(corner, origin), normal) / facing;
+    if distance < 0.0 {
+        return None;
+    }
+    let hit = [
+        origin[0] + direction[0] * distance,
+        origin[1] + direction[1] * distance,
+        origin[2] + direction[2] * distance,
+    ];
+    let offset = sub(hit, corner);
+    let u = dot(cross(offset, down), normal) / area;
+    let v = dot(cross(right, offset), normal) / area;
+    ((0.0..=1.0).contains(&u) && (0.0..=1.0).contains(&v)).then(|| egui::pos2(u, v))
+}
+
 #[inline]
 pub fn poiner_pos_in_points(
     egui_ctx: &egui::Context,
@@ -799,6 +1785,37 @@ pub fn into_egui_button(btn: MouseButton) -> Option<PointerButton> {
     }
 }
 
+/// What a key does to the on-screen keyboard while it shows.
+fn keycode_navigation(key: Keycode) -> Option<crate::keyboard::Navigation> {
+    use crate::keyboard::Navigation;
+    Some(match key {
+        Keycode::Up => Navigation::Up,
+        Keycode::Down => Navigation::Down,
+        Keycode::Left => Navigation::Left,
+        Keycode::Right => Navigation::Right,
+        Keycode::Return | Keycode::KpEnter => Navigation::Activate,
+        Keycode::Escape => Navigation::Hide,
+        _ => return None,
+    })
+}
+
+/// What a game controller button does to the on-screen keyboard while it
+/// shows: the d-pad moves, A presses, B hides, X is Backspace.
+fn controller_navigation(button: sdl2::controller::Button) -> Option<crate::keyboard::Navigation> {
+    use crate::keyboard::Navigation;
+    use sdl2::controller::Button;
+    Some(match button {
+        Button::DPadUp => Navigation::Up,
+        Button::DPadDown => Navigation::Down,
+        Button::DPadLeft => Navigation::Left,
+        Button::DPadRight => Navigation::Right,
+        Button::A => Navigation::Activate,
+        Button::B => Navigation::Hide,
+        Button::X => Navigation::Press(Key::Backspace),
+        _ => return None,
+    })
+}
+
 pub fn into_egui_key(key: Keycode) -> Option<Key> {
     Some(match key {
         Keycode::Left => Key::ArrowLeft,
@@ -813,6 +1830,9 @@ pub fn into_egui_key(key: Keycode) -> Option<Key> {
         Keycode::Return => Key::Enter,
 
         Keycode::Insert => Key::Insert,
+        Keycode::Copy => Key::Copy,
+        Keycode::Cut => Key::Cut,
+        Keycode::Paste => Key::Paste,
         Keycode::Home => Key::Home,
         Keycode::Delete => Key::Delete,
         Keycode::End => Key::End,
@@ -952,6 +1972,12 @@ pub fn into_egui_physical_key(scancode: Scancode) -> Option<Key> {
     }
 }
 
+/// A press the pointer finger made that egui has not heard of yet.
+struct TouchPress {
+    origin: egui::Pos2,
+    since: std::time::Instant,
+}
+
 struct TouchInfo {
     phase: egui::TouchPhase,
     touch_id: i64,
@@ -960,3 +1986,106 @@ struct TouchInfo {
     y: f32,
     pressure: f32,
 }
+
+/// The text a [`egui::output::OutputEvent::TextSelectionChanged`] reports as
+/// selected, if any is. egui counts the selection in chars. A password field
+/// reports its text masked, which is nothing worth offering.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn selected_text(info: &egui::WidgetInfo) -> Option<String> {
+    let range = info.text_selection.as_ref()?;
+    let (start, end) = (range.start.0, range.end.0);
+    let text = info.current_text_value.as_ref()?;
+    if text
+        .chars()
+        .all(|c| c == egui::epaint::text::PASSWORD_REPLACEMENT_CHAR)
+    {
+        return None;
+    }
+    (start < end).then(|| text.chars().skip(start).take(end - start).collect())
+}
+
+/// Clipboard text as egui takes a paste: Windows line endings made plain, and
+/// nothing at all for an empty clipboard.
+fn sanitize_paste(text: String) -> Option<String> {
+    let text = text.replace("\r\n", "\n");
+    (!text.is_empty()).then_some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clipboard_shortcuts_match_their_modifiers_exactly() {
+        use egui::{KeyboardShortcut, Modifiers};
+        let shortcuts = ClipboardShortcuts::default();
+        let paste = Some(ClipboardAction::Paste);
+        assert_eq!(shortcuts.action(Modifiers::COMMAND, Key::V), paste);
+        assert_eq!(shortcuts.action(Modifiers::NONE, Key::Paste), paste);
+        assert_eq!(shortcuts.action(Modifiers::NONE, Key::V), None);
+        assert_eq!(
+            shortcuts.action(Modifiers::COMMAND | Modifiers::SHIFT, Key::V),
+            None
+        );
+        if !cfg!(target_os = "macos") {
+            assert_eq!(shortcuts.action(Modifiers::SHIFT, Key::Insert), paste);
+        }
+
+        let rebound = shortcuts
+            .unbind(KeyboardShortcut::new(Modifiers::COMMAND, Key::V))
+            .bind(
+                KeyboardShortcut::new(Modifiers::COMMAND, Key::Y),
+                ClipboardAction::Paste,
+            );
+        assert_eq!(rebound.action(Modifiers::COMMAND, Key::V), None);
+        assert_eq!(rebound.action(Modifiers::COMMAND, Key::Y), paste);
+    }
+
+    #[test]
+    fn a_paste_is_sanitized() {
+        assert_eq!(
+            sanitize_paste("one\r\ntwo\n".to_owned()),
+            Some("one\ntwo\n".to_owned())
+        );
+        assert_eq!(sanitize_paste(String::new()), None);
+    }
+
+    #[test]
+    fn a_selection_is_cut_out_by_chars() {
+        let info = |start, end| {
+            egui::WidgetInfo::text_selection_changed(
+                true,
+                egui::text::CharIndex(start)..egui::text::CharIndex(end),
+                "naïve café",
+            )
+        };
+        assert_eq!(selected_text(&info(2, 5)), Some("ïve".to_owned()));
+        assert_eq!(selected_text(&info(6, 10)), Some("café".to_owned()));
+        assert_eq!(selected_text(&info(3, 3)), None, "collapsed");
+
+        let masked = egui::WidgetInfo::text_selection_changed(
+            true,
+            egui::text::CharIndex(0)..egui::text::CharIndex(3),
+            "•••",
+        );
+        assert_eq!(selected_text(&masked), None, "a password");
+    }
+
+    #[test]
+    fn a_ray_meets_a_panel_at_its_uv() {
+        // A 4 x 2 panel standing in the z = -5 plane, top-left at (-2, 1).
+        let corner = [-2.0, 1.0, -5.0];
+        let right = [4.0, 0.0, 0.0];
+        let down = [0.0, -2.0, 0.0];
+        let at = |origin, direction| ray_to_surface(origin, direction, corner, right, down);
+
+        let centre = at([0.0, 0.0, 0.0], [0.0, 0.0, -1.0]).unwrap();
+        assert!((centre - egui::pos2(0.5, 0.5)).length() < 1e-6);
+        let slanted = at([0.0, 0.0, 0.0], [-1.0, 0.5, -5.0]).unwrap();
+        assert!((slanted - egui::pos2(0.25, 0.25)).length() < 1e-6);
+
+        assert_eq!(at([0.0, 0.0, 0.0], [0.0, 0.0, 1.0]), None, "behind");
+        assert_eq!(at([0.0, 0.0, 0.0], [1.0, 0.0, 0.0]), None, "parallel");
+        assert_eq!(at([3.0, 0.0, 0.0], [0.0, 0.0, -1.0]), None, "beside");
+    }
+}
@@ -0,0 +1,309 @@
+//! A mouse pointer driven by a game controller or keys, for the widgets focus
+//! navigation cannot work — plots, sliders, colour pickers. See
+//! [`crate::State::set_virtual_pointer`].
+//!
+//! It moves the same pointer a mouse does, in egui's points, so it stays where
+//! it looks under any [`crate::Rotation`], and egui sees the same
+//! `PointerMoved`/`PointerButton` stream.
+
+use egui::{Pos2, Vec2};
+use sdl2::controller::Button;
+use sdl2::keyboard::Keycode;
+
+/// A control the virtual pointer can be bound to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PointerInput {
+    Button(Button),
+    Key(Keycode),
+}
+
+/// What a bound control does while held.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PointerAction {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    /// Press the primary button, as a left click.
+    Primary,
+    /// Press the secondary button, as a right click.
+    Secondary,
+    ScrollUp,
+    ScrollDown,
+}
+
+/// A game controller's analog stick.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Stick {
+    Left,
+    Right,
+}
+
+/// How the virtual pointer is driven.
+///
+/// [`Default`] moves it with the left stick and the d-pad, scrolls with the
+/// right stick and the shoulder buttons, and clicks with A and X. No keys are
+/// bound, as they would be taken from typing.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VirtualPointer {
+    pub bindings: Vec<(PointerInput, PointerAction)>,
+    /// The stick that moves the pointer.
+    pub move_stick: Option<Stick>,
+    /// The stick that scrolls.
+    pub scroll_stick: Option<Stick>,
+    /// How far a stick is pushed before it counts, from 0 to 1.
+    pub dead_zone: f32,
+    /// Points a second the pointer starts moving at.
+    pub speed: f32,
+    /// Points a second the pointer speeds up by each second it keeps moving.
+    pub acceleration: f32,
+    /// Points a second it speeds up to.
+    pub max_speed: f32,
+    /// Points a second a full push scrolls by.
+    pub scroll_speed: f32,
+}
+
+impl Default for VirtualPointer {
+    fn default() -> Self {
+        use PointerAction::*;
+        Self {
+            bindings: vec![
+                (PointerInput::Button(Button::DPadUp), MoveUp),
+                (PointerInput::Button(Button::DPadDown), MoveDown),
+                (PointerInput::Button(Button::DPadLeft), MoveLeft),
+                (PointerInput::Button(Button::DPadRight), MoveRight),
+                (PointerInput::Button(Button::A), Primary),
+                (PointerInput::Button(Button::X), Secondary),
+                (PointerInput::Button(Button::LeftShoulder), ScrollUp),
+                (PointerInput::Button(Button::RightShoulder), ScrollDown),
+            ],
+            move_stick: Some(Stick::Left),
+            scroll_stick: Some(Stick::Right),
+            dead_zone: 0.2,
+            speed: 150.0,
+            acceleration: 600.0,
+            max_speed: 900.0,
+            scroll_speed: 800.0,
+        }
+    }
+}
+
+impl VirtualPointer {
+    /// What `input` is bound to, if anything.
+    pub fn action(&self, input: PointerInput) -> Option<PointerAction> {
+        self.bindings
+            .iter()
+            .find(|(bound, _)| *bound == input)
+            .map(|&(_, action)| action)
+    }
+}
+
+/// What `State` keeps of the virtual pointer between frames.
+#[derive(Debug)]
+pub(crate) struct VirtualPointerState {
+    pub(crate) options: VirtualPointer,
+    /// The bound actions held down.
+    held: Vec<PointerAction>,
+    /// Each stick's push, from -1 to 1 on each axis, SDL's way up.
+    sticks: [Vec2; 2],
+    /// How long the pointer has kept moving, in seconds.
+    moving_for: f32,
+    last_tick: Option<std::time::Instant>,
+    /// Whether the pointer was last moved by this rather than a mouse, and so
+    /// needs drawing.
+    pub(crate) visible: bool,
+}
+
+impl VirtualPointerState {
+    pub(crate) fn new(options: VirtualPointer) -> Self {
+        Self {
+            options,
+            held: Vec::new(),
+            sticks: [Vec2::ZERO; 2],
+            moving_for: 0.0,
+            last_tick: None,
+            visible: false,
+        }
+    }
+
+    /// A control going down or up: the action it is bound to, or `None` if it
+    /// is not bound.
+    pub(crate) fn on_input(&mut self, input: PointerInput, pressed: bool) -> Option<PointerAction> {
+        let action = self.options.action(input)?;
+        self.held.retain(|held| *held != action);
+        if pressed {
+            self.held.push(action);
+        }
+        Some(action)
+    }
+
+    /// A stick moving, `value` as SDL reports it. Whether the stick is one the
+    /// pointer uses.
+    pub(crate) fn on_axis(&mut self, axis: sdl2::controller::Axis, value: i16) -> bool {
+        use sdl2::controller::Axis;
+        let (stick, vertical) = match axis {
+            Axis::LeftX => (Stick::Left, false),
+            Axis::LeftY => (Stick::Left, true),
+            Axis::RightX => (Stick::Right, false),
+            Axis::RightY => (Stick::Right, true),
+            _ => return false,
+        };
+        if self.options.move_stick != Some(stick) && self.options.scroll_stick != Some(stick) {
+            return false;
+        }
+        let push = &mut self.sticks[stick as usize];
+        let value = (value as f32 / i16::MAX as f32).clamp(-1.0, 1.0);
+        if vertical {
+            push.y = value;
+        } else {
+            push.x = value;
+        }
+        true
+    }
+
+    /// Where the held controls move the pointer and how far they scroll, in
+    /// points, since the last tick.
+    pub(crate) fn tick(&mut self, now: std::time::Instant) -> (Vec2, Vec2) {
+        let dt = self
+            .last_tick
+            .map_or(0.0, |last| (now - last).as_secs_f32().min(0.1));
+        self.last_tick = Some(now);
+        self.advance(dt)
+    }
+
+    /// Whether anything held keeps the pointer moving or scrolling, so egui
+    /// has to keep running frames.
+    pub(crate) fn is_active(&self) -> bool {
+        self.direction() != Vec2::ZERO || self.scroll() != Vec2::ZERO
+    }
+
+    fn advance(&mut self, dt: f32) -> (Vec2, Vec2) {
+        let direction = self.direction();
+        let motion = if direction == Vec2::ZERO {
+            self.moving_for = 0.0;
+            Vec2::ZERO
+        } else {
+            let options = &self.options;
+            let speed =
+                (options.speed + options.acceleration * self.moving_for).min(options.max_speed);
+            self.moving_for += dt;
+            direction * speed * dt
+        };
+        (motion, self.scroll() * self.options.scroll_speed * dt)
+    }
+
+    /// Which way the pointer moves, at most 1 long.
+    fn direction(&self) -> Vec2 {
+        let mut direction = Vec2::ZERO;
+        for action in &self.held {
+            match action {
+                PointerAction::MoveUp => direction.y -= 1.0,
+                PointerAction::MoveDown => direction.y += 1.0,
+                PointerAction::MoveLeft => direction.x -= 1.0,
+                PointerAction::MoveRight => direction.x += 1.0,
+                _ => {}
+            }
+        }
+        if let Some(stick) = self.stick(self.options.move_stick) {
+            direction += stick;
+        }
+        if direction.length() > 1.0 {
+            direction.normalized()
+        } else {
+            direction
+        }
+    }
+
+    /// How fast to scroll, as egui's wheel delta: up is positive.
+    fn scroll(&self) -> Vec2 {
+        let mut scroll = Vec2::ZERO;
+        for action in &self.held {
+            match action {
+                PointerAction::ScrollUp => scroll.y += 1.0,
+                PointerAction::ScrollDown => scroll.y -= 1.0,
+                _ => {}
+            }
+        }
+        if let Some(stick) = self.stick(self.options.scroll_stick) {
+            scroll -= stick;
+        }
+        scroll
+    }
+
+    /// The push of `stick` past the dead zone, eased so a small push moves
+    /// finely.
+    fn stick(&self, stick: Option<Stick>) -> Option<Vec2> {
+        let push = self.sticks[stick? as usize];
+        let length = push.length();
+        let dead_zone = self.options.dead_zone.clamp(0.0, 0.99);
+        if length <= dead_zone {
+            return None;
+        }
+        let eased = ((length.min(1.0) - dead_zone) / (1.0 - dead_zone)).powi(2);
+        Some(push / length * eased)
+    }
+}
+
+/// Paint the software cursor, an arrow with its tip at `pos`, over everything
+/// else in the frame.
+pub(crate) fn paint_cursor(ctx: &egui::Context, pos: Pos2) {
+    let painter = ctx.layer_painter(egui::LayerId::new(
+        egui::Order::Debug,
+        egui::Id::new("egui_sdl2_virtual_pointer"),
+    ));
+    let arrow = [
+        pos,
+        pos + egui::vec2(0.0, 17.0),
+        pos + egui::vec2(12.0, 12.0),
+    ];
+    painter.add(egui::Shape::convex_polygon(
+        arrow.to_vec(),
+        egui::Color32::WHITE,
+        egui::Stroke::new(1.5, egui::Color32::BLACK),
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_held_direction_speeds_up_to_the_cap() {
+        let mut pointer = VirtualPointerState::new(VirtualPointer::default());
+        let right = PointerInput::Button(Button::DPadRight);
+        assert_eq!(
+            pointer.on_input(right, true),
+            Some(PointerAction::MoveRight)
+        );
+
+        let (first, scroll) = pointer.advance(0.1);
+        assert_eq!(scroll, Vec2::ZERO);
+        assert!((first - egui::vec2(15.0, 0.0)).length() < 1e-3);
+        let (second, _) = pointer.advance(0.1);
+        assert!(second.x > first.x, "accelerates");
+        for _ in 0..100 {
+            pointer.advance(0.1);
+        }
+        let (capped, _) = pointer.advance(0.1);
+        assert!((capped.x - 90.0).abs() < 1e-3);
+
+        pointer.on_input(right, false);
+        assert_eq!(pointer.advance(0.1).0, Vec2::ZERO);
+        assert!(!pointer.is_active());
+    }
+
+    #[test]
+    fn sticks_move_and_scroll_past_the_dead_zone() {
+        use sdl2::controller::Axis;
+        let mut pointer = VirtualPointerState::new(VirtualPointer::default());
+        assert!(pointer.on_axis(Axis::LeftX, i16::MAX / 10));
+        assert_eq!(pointer.advance(0.1), (Vec2::ZERO, Vec2::ZERO));
+
+        assert!(pointer.on_axis(Axis::LeftX, i16::MAX));
+        assert!(pointer.on_axis(Axis::RightY, i16::MIN));
+        let (motion, scroll) = pointer.advance(0.1);
+        assert!(motion.x > 0.0 && motion.y == 0.0);
+        assert!(scroll.y > 0.0, "pushed up scrolls up");
+        assert!(!pointer.on_axis(Axis::TriggerLeft, i16::MAX));
+    }
+}
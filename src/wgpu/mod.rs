@@ -40,6 +40,63 @@ impl HasDisplayHandle for SdlDisplayHandle {
     }
 }
 
+/// How [`EguiWgpu`] sets wgpu up. The defaults are what [`EguiWgpu::new`] uses,
+/// with the power preference and backends read from `WGPU_POWER_PREF` and
+/// `WGPU_BACKEND` when set.
+#[derive(Clone, Debug)]
+pub struct WgpuOptions {
+    /// Samples per pixel; 1 is no multisampling.
+    pub msaa_samples: u32,
+    /// Depth/stencil buffer for paint callbacks that want one.
+    pub depth_format: Option<egui_wgpu::wgpu::TextureFormat>,
+    /// Ask for a surface that composites with what is behind the window.
+    pub transparent: bool,
+    /// Dither egui's gradients against banding.
+    pub dithering: bool,
+    pub present_mode: egui_wgpu::wgpu::PresentMode,
+    /// Frames the presentation engine may queue; `Some(1)` for the lowest
+    /// latency, `None` for wgpu's default.
+    pub desired_maximum_frame_latency: Option<u32>,
+    pub power_preference: egui_wgpu::wgpu::PowerPreference,
+    /// The APIs wgpu may pick an adapter from. `Backends::GL` alone runs on a
+    /// machine with OpenGL but no Vulkan.
+    pub backends: egui_wgpu::wgpu::Backends,
+}
+
+impl Default for WgpuOptions {
+    fn default() -> Self {
+        let surface = egui_wgpu::WgpuConfiguration::default().surface;
+        let setup = egui_wgpu::WgpuSetupCreateNew::without_display_handle();
+        Self {
+            msaa_samples: 1,
+            depth_format: None,
+            transparent: true,
+            dithering: false,
+            present_mode: surface.present_mode,
+            desired_maximum_frame_latency: surface.desired_maximum_frame_latency,
+            power_preference: setup.power_preference,
+            backends: setup.instance_descriptor.backends,
+        }
+    }
+}
+
+impl WgpuOptions {
+    fn configuration(
+        &self,
+        wgpu_setup: Option<egui_wgpu::WgpuSetup>,
+    ) -> egui_wgpu::WgpuConfiguration {
+        let default = egui_wgpu::WgpuConfiguration::default();
+        egui_wgpu::WgpuConfiguration {
+            surface: egui_wgpu::SurfaceConfig {
+                present_mode: self.present_mode,
+                desired_maximum_frame_latency: self.desired_maximum_frame_latency,
+            },
+            wgpu_setup: wgpu_setup.unwrap_or(default.wgpu_setup),
+            ..default
+        }
+    }
+}
+
 /// Integration between [`egui`] and [`wgpu`](https://docs.rs/wgpu) for app based on [`sdl2`].
 pub struct EguiWgpu {
     run_output: crate::EguiRunOutput,
@@ -51,36 +108,92 @@ pub struct EguiWgpu {
 }
 
 impl EguiWgpu {
+    /// Panics if wgpu cannot present to the window; [`Self::try_new`] reports it.
     pub async fn new(window: sdl2::video::Window) -> Self {
+        Self::try_new(window).await.unwrap_or_else(|err| {
+            log::error!("error occurred in initializing wgpu:\n{err}");
+            panic!("{err}")
+        })
+    }
+
+    /// [`Self::new`], returning an error where the machine has no adapter for
+    /// the window or refuses its surface, so the caller can try another
+    /// renderer.
+    pub async fn try_new(window: sdl2::video::Window) -> Result<Self, WgpuSetupError> {
+        Self::with_options(window, WgpuOptions::default()).await
+    }
+
+    /// [`Self::try_new`], set up as `options` says.
+    pub async fn with_options(
+        window: sdl2::video::Window,
+        options: WgpuOptions,
+    ) -> Result<Self, WgpuSetupError> {
+        let raw_display_handle = window
+            .display_handle()
+            .map_err(|e| WgpuSetupError::SurfaceCreation(Box::new(e)))?
+            .as_raw();
+        let mut setup = egui_wgpu::WgpuSetupCreateNew::from_display_handle(SdlDisplayHandle(
+            raw_display_handle,
+        ));
+        setup.power_preference = options.power_preference;
+        setup.instance_descriptor.backends = options.backends;
         let ctx = egui::Context::default();
+        let painter = painter::Painter::new(
+            ctx.clone(),
+            options.configuration(Some(setup.into())),
+            options.msaa_samples,
+            options.depth_format,
+            options.transparent,
+            options.dithering,
+        )
+        .await;
+        Self::with_painter(window, ctx, painter).await
+    }
+
+    /// [`Self::with_options`] on the app's existing device, so egui and the app
+    /// share one wgpu. The power preference and backends in `options` go
+    /// unused, and its MSAA, depth format and dithering must be what
+    /// `render_state`'s renderer was made with.
+    pub async fn with_render_state(
+        window: sdl2::video::Window,
+        render_state: egui_wgpu::RenderState,
+        options: WgpuOptions,
+    ) -> Result<Self, WgpuSetupError> {
+        let ctx = egui::Context::default();
+        let painter = painter::Painter::from_render_state(
+            ctx.clone(),
+            options.configuration(None),
+            render_state,
+            options.msaa_samples,
+            options.depth_format,
+            options.transparent,
+            options.dithering,
+        );
+        Self::with_painter(window, ctx, painter).await
+    }
+
+    async fn with_painter(
+        window: sdl2::video::Window,
+        ctx: egui::Context,
+        mut painter: painter::Painter,
+    ) -> Result<Self, WgpuSetupError> {
         let viewport_id = egui::ViewportId::ROOT;
         let state = crate::State::new(&window, ctx.clone(), viewport_id);
         let run_output = crate::EguiRunOutput::default();
-        let raw_display_handle = window
-            .display_handle()
-            .expect("SDL2 window must expose a display handle")
-            .as_raw();
-        let config = egui_wgpu::WgpuConfiguration {
-            wgpu_setup: egui_wgpu::WgpuSetup::from_display_handle(SdlDisplayHandle(
-                raw_display_handle,
-            )),
-            ..Default::default()
-        };
-        let mut painter = painter::Painter::new(ctx.clone(), config, 1, None, true, false).await;
         // SAFETY:
         // Window lives as long as self
         unsafe {
-            painter.set_window(viewport_id, &window).await.unwrap();
+            painter.set_window(viewport_id, &window).await?;
         }
 
-        Self {
+        Ok(Self {
             window,
             ctx,
             painter,
             state,
             run_output,
             viewport_id,
-        }
+        })
     }
 
     #[inline]
@@ -106,6 +219,7 @@ impl EguiWgpu {
     /// Call [`Self::paint`] later to paint.
     #[inline]
     pub fn run(&mut self, run_ui: impl FnMut(&egui::Context)) {
+        self.deliver_screenshots();
         self.run_output.update(&self.ctx, &mut self.state, run_ui);
     }
 
@@ -113,6 +227,7 @@ impl EguiWgpu {
     /// is what panels are shown into.
     #[inline]
     pub fn run_ui(&mut self, run_ui: impl FnMut(&mut egui::Ui)) {
+        self.deliver_screenshots();
         self.run_output
             .update_ui(&self.ctx, &mut self.state, run_ui);
     }
@@ -124,6 +239,23 @@ impl EguiWgpu {
         self.run_output.repaint_delay
     }
 
+    /// Pass on the screenshots the GPU has finished copying out. The capture is
+    /// of the surface, so a turn is taken back out of it first.
+    fn deliver_screenshots(&mut self) {
+        let mut events = Vec::new();
+        self.painter.handle_screenshots(&mut events);
+        let rotation = self.state.rotation();
+        for event in events {
+            if let egui::Event::Screenshot {
+                user_data, image, ..
+            } = event
+            {
+                let image = std::sync::Arc::new(rotation.unturn_image(&image));
+                self.state.on_screenshot(user_data, image);
+            }
+        }
+    }
+
     /// Paint the results of the last call to [`Self::run`].
     pub fn paint(&mut self, clear_color: [f32; 4]) {
         let pixels_per_point = self.run_output.pixels_per_point;
@@ -143,7 +275,7 @@ impl EguiWgpu {
             clear_color,
             &clipped_primitives,
             &mut textures_delta,
-            Vec::with_capacity(0),
+            std::mem::take(&mut self.run_output.screenshots),
         );
     }
 }
@@ -7,6 +7,51 @@ use egui_wgpu::wgpu::{
 use egui_wgpu::{RenderState, RendererOptions, SurfaceErrorAction, WgpuConfiguration, WgpuError};
 use std::{num::NonZeroU32, sync::Arc};
 
+/// Why the painter could not present to a window.
+#[derive(Debug)]
+pub enum WgpuSetupError {
+    /// No adapter, or no device on one, that can present to the window: a
+    /// machine without Vulkan, Metal, DX12 or GL that wgpu can drive, or a
+    /// broken driver.
+    NoAdapter(WgpuError),
+    /// The window's handles were refused, or the surface built on them.
+    SurfaceCreation(Box<dyn std::error::Error + Send + Sync>),
+    /// The adapter has no configuration it can present this surface with.
+    UnsupportedSurface,
+}
+
+impl std::fmt::Display for WgpuSetupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoAdapter(e) => write!(f, "no usable wgpu adapter: {e}"),
+            Self::SurfaceCreation(e) => write!(f, "could not create a wgpu surface: {e}"),
+            Self::UnsupportedSurface => {
+                f.write_str("the wgpu adapter does not support the surface")
+            }
+        }
+    }
+}
+
+impl std::error::Error for WgpuSetupError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::NoAdapter(e) => Some(e),
+            Self::SurfaceCreation(e) => Some(e.as_ref()),
+            Self::UnsupportedSurface => None,
+        }
+    }
+}
+
+impl From<WgpuError> for WgpuSetupError {
+    fn from(e: WgpuError) -> Self {
+        match e {
+            WgpuError::CreateSurfaceError(e) => Self::SurfaceCreation(Box::new(e)),
+            WgpuError::NoSurfaceFormatsAvailable => Self::UnsupportedSurface,
+            e => Self::NoAdapter(e),
+        }
+    }
+}
+
 struct SurfaceState {
     surface: Surface<'static>,
     alpha_mode: CompositeAlphaMode,
@@ -56,9 +101,54 @@ impl Painter {
         support_transparent_backbuffer: bool,
         dithering: bool,
     ) -> Self {
-        let (capture_tx, capture_rx) = std::sync::mpsc::channel();
         let instance = configuration.wgpu_setup.new_instance().await;
+        Self::with_instance(
+            context,
+            configuration,
+            instance,
+            msaa_samples,
+            depth_format,
+            support_transparent_backbuffer,
+            dithering,
+        )
+    }
+
+    /// [`Self::new`] on the app's own adapter and device, so egui draws with the
+    /// wgpu the app already set up. `configuration.wgpu_setup` goes unused, and
+    /// `msaa_samples`, `depth_format` and `dithering` must be what the render
+    /// state's [`egui_wgpu::Renderer`] was made with.
+    pub fn from_render_state(
+        context: egui::Context,
+        configuration: WgpuConfiguration,
+        render_state: RenderState,
+        msaa_samples: u32,
+        depth_format: Option<TextureFormat>,
+        support_transparent_backbuffer: bool,
+        dithering: bool,
+    ) -> Self {
+        let mut painter = Self::with_instance(
+            context,
+            configuration,
+            render_state.instance.clone(),
+            msaa_samples,
+            depth_format,
+            support_transparent_backbuffer,
+            dithering,
+        );
+        painter.render_state = Some(render_state);
+        painter
+    }
 
+    fn with_instance(
+        context: egui::Context,
+        configuration: WgpuConfiguration,
+        instance: egui_wgpu::wgpu::Instance,
+        msaa_samples: u32,
+        depth_format: Option<TextureFormat>,
+        support_transparent_backbuffer: bool,
+        dithering: bool,
+    ) -> Self {
+        let (capture_tx, capture_rx) = std::sync::mpsc::channel();
         Self {
             context,
             configuration,
@@ -100,12 +190,15 @@ impl Painter {
         &mut self,
         viewport_id: ViewportId,
         window: &sdl2::video::Window,
-    ) -> Result<(), WgpuError> {
+    ) -> Result<(), WgpuSetupError> {
         let size = window.size();
         if !self.surfaces.contains_key(&viewport_id) {
             let surface = unsafe {
-                let target = SurfaceTargetUnsafe::from_window(&window).unwrap();
-                self.instance.create_surface_unsafe(target)?
+                let target = SurfaceTargetUnsafe::from_window(&window)
+                    .map_err(|e| WgpuSetupError::SurfaceCreation(Box::new(e)))?;
+                self.instance
+                    .create_surface_unsafe(target)
+                    .map_err(|e| WgpuSetupError::SurfaceCreation(Box::new(e)))?
             };
             self.add_surface(surface, viewport_id, size).await?;
         }
@@ -118,7 +211,7 @@ impl Painter {
         surface: Surface<'static>,
         viewport_id: ViewportId,
         size: (u32, u32),
-    ) -> Result<(), WgpuError> {
+    ) -> Result<(), WgpuSetupError> {
         let render_state = if let Some(render_state) = &self.render_state {
             render_state
         } else {
@@ -167,8 +260,7 @@ impl Painter {
             log::debug!("The window height was zero; skipping generate textures");
             return Ok(());
         };
-        self.resize_and_generate_depth_texture_view_and_msaa_view(viewport_id, width, height);
-        Ok(())
+        self.resize_and_generate_depth_texture_view_and_msaa_view(viewport_id, width, height)
     }
 
     /// Returns the maximum texture dimension supported if known
@@ -187,7 +279,7 @@ impl Painter {
         viewport_id: ViewportId,
         width_in_pixels: NonZeroU32,
         height_in_pixels: NonZeroU32,
-    ) {
+    ) -> Result<(), WgpuSetupError> {
         let width = width_in_pixels.get();
         let height = height_in_pixels.get();
 
@@ -197,7 +289,7 @@ impl Painter {
         surface_state.width = width;
         surface_state.height = height;
 
-        configure_surface(surface_state, render_state, &self.configuration);
+        configure_surface(surface_state, render_state, &self.configuration)?;
 
         if let Some(depth_format) = self.depth_format {
             self.depth_texture_view.insert(
@@ -249,6 +341,7 @@ impl Painter {
                     .create_view(&egui_wgpu::wgpu::TextureViewDescriptor::default()),
             );
         }
+        Ok(())
     }
 
     pub fn on_window_resized(
@@ -258,11 +351,13 @@ impl Painter {
         height_in_pixels: NonZeroU32,
     ) {
         if self.surfaces.contains_key(&viewport_id) {
-            self.resize_and_generate_depth_texture_view_and_msaa_view(
+            if let Err(e) = self.resize_and_generate_depth_texture_view_and_msaa_view(
                 viewport_id,
                 width_in_pixels,
                 height_in_pixels,
-            );
+            ) {
+                log::warn!("Failed to resize the surface: {e}");
+            }
         } else {
             log::warn!(
                 "Ignoring window resize notification with no surface created via Painter::set_window()"
@@ -352,7 +447,11 @@ impl Painter {
                 // fresh surface object. This painter only reconfigures in place, so both map to
                 // the same recovery path: reconfigure and skip the current frame.
                 SurfaceErrorAction::Reconfigure | SurfaceErrorAction::RecreateSurface => {
-                    configure_surface(surface_state, render_state, &self.configuration);
+                    if let Err(e) =
+                        configure_surface(surface_state, render_state, &self.configuration)
+                    {
+                        log::warn!("Failed to reconfigure the surface: {e}");
+                    }
                     return vsync_sec;
                 }
                 SurfaceErrorAction::SkipFrame => {
@@ -501,7 +600,7 @@ fn configure_surface(
     surface_state: &SurfaceState,
     render_state: &RenderState,
     config: &WgpuConfiguration,
-) {
+) -> Result<(), WgpuSetupError> {
     let width = surface_state.width;
     let height = surface_state.height;
 
@@ -514,7 +613,7 @@ fn configure_surface(
         ..surface_state
             .surface
             .get_default_config(&render_state.adapter, width, height)
-            .expect("The surface isn't supported by this adapter")
+            .ok_or(WgpuSetupError::UnsupportedSurface)?
     };
 
     if let Some(desired_maximum_frame_latency) = config.surface.desired_maximum_frame_latency {
@@ -524,4 +623,5 @@ fn configure_surface(
     surface_state
         .surface
         .configure(&render_state.device, &surf_config);
+    Ok(())
 }
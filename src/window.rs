@@ -33,9 +33,13 @@
 #[cfg(feature = "canvas-backend")]
 use crate::canvas::painter::BYTES_PER_PIXEL;
 use crate::Rotation;
+use egui::epaint::ImageDelta;
+use egui::{Color32, ColorImage, TextureId, TextureOptions};
 use sdl2::event::Event;
 use sdl2::video::{Window, WindowBuilder};
 use sdl2::VideoSubsystem;
+use std::collections::HashMap;
+use std::sync::Arc;
 
 /// A way to put egui on screen. Apps list the ones they accept, best first.
 #[non_exhaustive]
@@ -46,6 +50,12 @@ pub enum Renderer {
     Gles3,
     /// Desktop OpenGL 3.2 core through glow.
     Gl32,
+    /// OpenGL ES 2.0 with egui's GLSL ES 1.00 shaders, for the older handhelds
+    /// and SBCs whose drivers stop there.
+    Gles2,
+    /// Desktop OpenGL 2.1 with egui's GLSL 1.20 shaders, for GPUs that predate
+    /// core profiles.
+    Gl21,
     /// SDL's own 2D renderer: an accelerated driver when SDL finds one, its
     /// software rasterizer otherwise. The one that needs no GL at all.
     Canvas,
@@ -58,13 +68,21 @@ pub enum Renderer {
 }
 
 impl Renderer {
-    /// GL first, SDL's renderer as the safety net.
-    pub const FALLBACK_CHAIN: [Renderer; 3] = [Renderer::Gles3, Renderer::Gl32, Renderer::Canvas];
+    /// GL from newest to oldest, SDL's renderer as the safety net.
+    pub const FALLBACK_CHAIN: [Renderer; 5] = [
+        Renderer::Gles3,
+        Renderer::Gl32,
+        Renderer::Gles2,
+        Renderer::Gl21,
+        Renderer::Canvas,
+    ];
 
     fn name(self) -> &'static str {
         match self {
             Renderer::Gles3 => "GLES 3.0",
             Renderer::Gl32 => "GL 3.2 core",
+            Renderer::Gles2 => "GLES 2.0",
+            Renderer::Gl21 => "GL 2.1",
             Renderer::Canvas => "SDL renderer",
             Renderer::CanvasBlit => "SDL renderer (offscreen blit)",
             Renderer::Wgpu => "wgpu",
@@ -111,6 +129,21 @@ enum Backend {
 pub struct EguiWindow {
     backend: Backend,
     renderer: Renderer,
+    /// Declared after the backend so SDL's hold on it outlasts the window.
+    hit_test: Option<crate::hit_test::HitTest>,
+    /// Frames the app hands over with [`Self::update_rgba_texture`], the last
+    /// of each kept to rebuild from when a backend loses its textures.
+    rgba_textures: HashMap<TextureId, RgbaTexture>,
+    /// The id the next [`Self::register_rgba_texture`] hands out on wgpu,
+    /// whose renderer only numbers the textures it is given a view of. The
+    /// other backends number them with their painter's own ids.
+    #[cfg(feature = "wgpu-backend")]
+    next_rgba_id: u64,
+}
+
+struct RgbaTexture {
+    image: Arc<ColorImage>,
+    options: TextureOptions,
 }
 
 impl EguiWindow {
@@ -122,6 +155,33 @@ impl EguiWindow {
         size: (u32, u32),
         configure: impl Fn(&mut WindowBuilder),
         order: &[Renderer],
+    ) -> Result<Self, String> {
+        Self::open(video, title, size, configure, order, false)
+    }
+
+    /// [`Self::new`] with a framebuffer that keeps its alpha, so what
+    /// [`Self::paint`] clears to and egui leaves uncovered shows the desktop
+    /// through. The GL renderers ask for an alpha channel and wgpu for a
+    /// compositing surface; SDL's renderer has no such thing, and a window on a
+    /// driver without it comes up opaque. Make it borderless in `configure`,
+    /// and see [`Self::set_hit_test`] for a title bar of egui's own.
+    pub fn new_transparent(
+        video: &VideoSubsystem,
+        title: &str,
+        size: (u32, u32),
+        configure: impl Fn(&mut WindowBuilder),
+        order: &[Renderer],
+    ) -> Result<Self, String> {
+        Self::open(video, title, size, configure, order, true)
+    }
+
+    fn open(
+        video: &VideoSubsystem,
+        title: &str,
+        size: (u32, u32),
+        configure: impl Fn(&mut WindowBuilder),
+        order: &[Renderer],
+        transparent: bool,
     ) -> Result<Self, String> {
         let make_window = |video: &VideoSubsystem, gl: bool| {
             let mut builder = video.window(title, size.0, size.1);
@@ -133,10 +193,18 @@ impl EguiWindow {
         };
         let mut last = "no renderer requested".to_string();
         for &renderer in order {
-            match build(video, &make_window, renderer) {
+            match build(video, &make_window, renderer, transparent) {
                 Ok(backend) => {
                     log::info!("egui renderer: {}", renderer.name());
-                    return Ok(Self { backend, renderer });
+                    return Ok(Self {
+                        backend,
+                        renderer,
+                        hit_test: None,
+                        rgba_textures: HashMap::new(),
+                        // Clear of the app's own ids, as the painters' are.
+                        #[cfg(feature = "wgpu-backend")]
+                        next_rgba_id: 1 << 32,
+                    });
                 }
                 Err(e) => {
                     log::warn!("{} unavailable: {e}", renderer.name());
@@ -152,12 +220,12 @@ impl EguiWindow {
         self.renderer
     }
 
-    /// Present the UI at a quarter turn to the window, for a panel that is not
-    /// mounted the way it is read.
+    /// Present the UI at a quarter turn to the window, or mirrored, for a panel
+    /// that is not mounted the way it is read.
     ///
     /// egui lays out for the turned screen — a quarter turn trades the window's
     /// width and height — and this window puts the frame back on the panel:
-    /// [`Renderer::Gles3`] and [`Renderer::Gl32`] turn the geometry as they draw
+    /// the GL renderers turn the geometry as they draw
     /// it, the SDL renderers paint offscreen and present that turned. Pointer
     /// and touch positions travel back the same way, so a tap lands where it
     /// looks. May be called at any time; nothing is rebuilt on a change of turn.
@@ -200,6 +268,35 @@ impl EguiWindow {
         }
     }
 
+    /// The input side of whichever backend is running, for what the window has
+    /// no method of its own for: the clipboard shortcuts, the on-screen
+    /// keyboard.
+    pub fn state(&self) -> &crate::State {
+        match &self.backend {
+            #[cfg(feature = "glow-backend")]
+            Backend::Glow { egui, .. } => &egui.state,
+            #[cfg(feature = "canvas-backend")]
+            Backend::Canvas { egui, .. } => &egui.state,
+            #[cfg(feature = "canvas-backend")]
+            Backend::CanvasBlit { egui, .. } => &egui.state,
+            #[cfg(feature = "wgpu-backend")]
+            Backend::Wgpu { egui } => &egui.state,
+        }
+    }
+
+    pub fn state_mut(&mut self) -> &mut crate::State {
+        match &mut self.backend {
+            #[cfg(feature = "glow-backend")]
+            Backend::Glow { egui, .. } => &mut egui.state,
+            #[cfg(feature = "canvas-backend")]
+            Backend::Canvas { egui, .. } => &mut egui.state,
+            #[cfg(feature = "canvas-backend")]
+            Backend::CanvasBlit { egui, .. } => &mut egui.state,
+            #[cfg(feature = "wgpu-backend")]
+            Backend::Wgpu { egui } => &mut egui.state,
+        }
+    }
+
     pub fn window(&self) -> &Window {
         match &self.backend {
             #[cfg(feature = "glow-backend")]
@@ -228,6 +325,122 @@ impl EguiWindow {
         }
     }
 
+    /// Show frames the app produces — a video, an emulator's screen — as an
+    /// egui image, whichever renderer won. `rgba` is `size[0] * size[1]` pixels
+    /// row by row, four bytes each, sRGB with premultiplied alpha as in
+    /// [`Color32`]; for an opaque frame that is plain RGBA. Each backend keeps
+    /// it in a texture of its own: a streaming one on the SDL renderers, a GL
+    /// or wgpu one otherwise.
+    pub fn register_rgba_texture(
+        &mut self,
+        size: [usize; 2],
+        rgba: &[u8],
+        options: TextureOptions,
+    ) -> Result<TextureId, String> {
+        let mut image = ColorImage::new(size, vec![Color32::TRANSPARENT; size[0] * size[1]]);
+        fill_image(&mut image, rgba)?;
+        let id = self.next_rgba_id()?;
+        let image = Arc::new(image);
+        self.upload(id, &ImageDelta::full(image.clone(), options))?;
+        self.rgba_textures
+            .insert(id, RgbaTexture { image, options });
+        Ok(id)
+    }
+
+    /// Replace a registered texture's pixels with the next frame, the same size
+    /// as the first. It is written over in place: `SDL_UpdateTexture`,
+    /// `glTexSubImage2D` or `queue.write_texture`.
+    pub fn update_rgba_texture(&mut self, id: TextureId, rgba: &[u8]) -> Result<(), String> {
+        let Some(texture) = self.rgba_textures.get_mut(&id) else {
+            return Err(format!("{id:?} is not a registered RGBA texture"));
+        };
+        // No backend holds on to the last delta, so this refills in place.
+        fill_image(Arc::make_mut(&mut texture.image), rgba)?;
+        let delta = ImageDelta::partial([0, 0], texture.image.clone(), texture.options);
+        self.upload(id, &delta)
+    }
+
+    /// Release a texture from [`Self::register_rgba_texture`].
+    pub fn free_rgba_texture(&mut self, id: TextureId) {
+        if self.rgba_textures.remove(&id).is_none() {
+            return;
+        }
+        match &mut self.backend {
+            #[cfg(feature = "glow-backend")]
+            Backend::Glow { egui, .. } => egui.painter.free_texture(id),
+            #[cfg(feature = "canvas-backend")]
+            Backend::Canvas { egui, .. } => egui.painter.free_texture(&id),
+            #[cfg(feature = "canvas-backend")]
+            Backend::CanvasBlit { egui, .. } => egui.painter.free_texture(&id),
+            #[cfg(feature = "wgpu-backend")]
+            Backend::Wgpu { egui } => {
+                if let Some(render_state) = egui.painter.render_state() {
+                    render_state.renderer.write().free_texture(&id);
+                }
+            }
+        }
+    }
+
+    /// An id for a new RGBA texture from the backend's own numbering, so it
+    /// meets neither the app's `TextureId::User` ids nor the painter's.
+    fn next_rgba_id(&mut self) -> Result<TextureId, String> {
+        match &mut self.backend {
+            #[cfg(feature = "glow-backend")]
+            Backend::Glow { egui, .. } => {
+                use glow::HasContext as _;
+                // egui_glow numbers the textures it is given, and fills this
+                // one in with the first upload.
+                let texture = unsafe { egui.painter.gl().create_texture() }?;
+                Ok(egui.painter.register_native_texture(texture))
+            }
+            #[cfg(feature = "canvas-backend")]
+            Backend::Canvas { egui, .. } => Ok(egui.painter.next_texture_id()),
+            #[cfg(feature = "canvas-backend")]
+            Backend::CanvasBlit { egui, .. } => Ok(egui.painter.next_texture_id()),
+            #[cfg(feature = "wgpu-backend")]
+            Backend::Wgpu { .. } => {
+                let id = TextureId::User(self.next_rgba_id);
+                self.next_rgba_id += 1;
+                Ok(id)
+            }
+        }
+    }
+
+    /// Hand a delta for one of the app's textures to the backend, to go up in
+    /// full now; none of them keeps it afterwards.
+    fn upload(&mut self, id: TextureId, delta: &ImageDelta) -> Result<(), String> {
+        match &mut self.backend {
+            #[cfg(feature = "glow-backend")]
+            Backend::Glow { egui, .. } => {
+                egui.painter.set_texture(id, delta);
+                Ok(())
+            }
+            #[cfg(feature = "canvas-backend")]
+            Backend::Canvas { egui, .. } => egui
+                .painter
+                .set_texture_now(id, delta)
+                .map_err(|e| e.to_string()),
+            #[cfg(feature = "canvas-backend")]
+            Backend::CanvasBlit { egui, .. } => egui
+                .painter
+                .set_texture_now(id, delta)
+                .map_err(|e| e.to_string()),
+            #[cfg(feature = "wgpu-backend")]
+            Backend::Wgpu { egui } => {
+                let Some(render_state) = egui.painter.render_state() else {
+                    return Err("wgpu has no device yet".to_string());
+                };
+                render_state.renderer.write().update_texture(
+                    &render_state.device,
+                    &render_state.queue,
+                    id,
+                    delta,
+                );
+                Ok(())
+            }
+        }
+    }
+
     /// Feed an SDL event to egui; wgpu also resizes its surface here.
     pub fn on_event(&mut self, event: &Event) -> crate::EventResponse {
         match &mut self.backend {
@@ -254,6 +467,7 @@ impl EguiWindow {
             #[cfg(feature = "wgpu-backend")]
             Backend::Wgpu { egui } => egui.run(run_ui),
         }
+        self.update_hit_test();
     }
 
     /// Like [`Self::run`], but hands the closure egui's root [`egui::Ui`].
@@ -268,6 +482,28 @@ impl EguiWindow {
             #[cfg(feature = "wgpu-backend")]
             Backend::Wgpu { egui } => egui.run_ui(run_ui),
         }
+        self.update_hit_test();
+    }
+
+    /// Let egui say which parts of the window move or resize it, for a
+    /// borderless window with a title bar of its own: the UI marks them each
+    /// frame with [`crate::set_hit_area`], and SDL hands presses there to the
+    /// window manager, which moves or resizes the window natively. Fails where
+    /// the platform has no hit test.
+    pub fn set_hit_test(&mut self, enabled: bool) -> Result<(), String> {
+        match (enabled, self.hit_test.take()) {
+            (true, None) => self.hit_test = Some(crate::hit_test::HitTest::enable(self.window())?),
+            (true, Some(hit_test)) => self.hit_test = Some(hit_test),
+            (false, Some(hit_test)) => hit_test.disable(self.window()),
+            (false, None) => {}
+        }
+        Ok(())
+    }
+
+    fn update_hit_test(&self) {
+        if let Some(hit_test) = &self.hit_test {
+            hit_test.update(self.ctx(), self.window(), self.rotation());
+        }
     }
 
     /// How long until egui wants another frame, from the last [`Self::run`].
@@ -303,7 +539,7 @@ impl EguiWindow {
                 if rotation == Rotation::None {
                     canvas.set_draw_color(rgb(clear_color));
                     canvas.clear();
-                    egui.paint(canvas);
+                    paint_canvas(egui, canvas, false);
                     canvas.present();
                 } else {
                     paint_turned(canvas, egui, turned, rotation, clear_color);
@@ -333,6 +569,14 @@ impl EguiWindow {
                             // The fresh state starts unturned; the window's turn
                             // outlives the target it was being presented on.
                             egui.state.set_rotation(rotation);
+                            // The app's textures went with the old painter.
+                            for (id, texture) in &self.rgba_textures {
+                                let delta =
+                                    ImageDelta::full(texture.image.clone(), texture.options);
+                                if let Err(e) = egui.painter.set_texture_now(*id, &delta) {
+                                    log::warn!("{e}");
+                                }
+                            }
                             *offscreen = new_offscreen;
                             *present = new_present;
                             *size = new_size;
@@ -343,7 +587,7 @@ impl EguiWindow {
 
                 offscreen.set_draw_color(rgb(clear_color));
                 offscreen.clear();
-                egui.paint(offscreen);
+                paint_canvas(egui, offscreen, true);
                 let rotation = egui.state.rotation();
                 let surface = offscreen.surface();
                 let pitch = surface.pitch() as usize;
@@ -400,13 +644,18 @@ fn build(
     video: &VideoSubsystem,
     make_window: &impl Fn(&VideoSubsystem, bool) -> Result<Window, String>,
     renderer: Renderer,
+    transparent: bool,
 ) -> Result<Backend, String> {
+    if transparent && matches!(renderer, Renderer::Canvas | Renderer::CanvasBlit) {
+        log::info!("{} draws an opaque window", renderer.name());
+    }
     match renderer {
-        Renderer::Gles3 => build_glow(video, make_window, sdl2::video::GLProfile::GLES, 3, 0),
-        Renderer::Gl32 => build_glow(video, make_window, sdl2::video::GLProfile::Core, 3, 2),
+        Renderer::Gles3 | Renderer::Gl32 | Renderer::Gles2 | Renderer::Gl21 => {
+            build_glow(video, make_window, renderer, transparent)
+        }
         Renderer::Canvas => build_canvas(video, make_window),
         Renderer::CanvasBlit => build_canvas_blit(video, make_window),
-        Renderer::Wgpu => build_wgpu(video, make_window),
+        Renderer::Wgpu => build_wgpu(video, make_window, transparent),
     }
 }
 
@@ -431,14 +680,37 @@ fn gl_profile_value(profile: sdl2::video::GLProfile) -> i32 {
     }
 }
 
+/// The context a GL renderer asks SDL for, and the shaders egui is to use on
+/// it; `None` lets egui_glow read them off the context.
+#[cfg(feature = "glow-backend")]
+fn gl_request(
+    renderer: Renderer,
+) -> (
+    sdl2::video::GLProfile,
+    u8,
+    u8,
+    Option<egui_glow::ShaderVersion>,
+) {
+    use egui_glow::ShaderVersion;
+    use sdl2::video::GLProfile;
+    match renderer {
+        Renderer::Gles3 => (GLProfile::GLES, 3, 0, None),
+        Renderer::Gl32 => (GLProfile::Core, 3, 2, None),
+        Renderer::Gles2 => (GLProfile::GLES, 2, 0, Some(ShaderVersion::Es100)),
+        // Core profiles start at 3.2; a 2.1 context is a compatibility one.
+        Renderer::Gl21 => (GLProfile::Compatibility, 2, 1, Some(ShaderVersion::Gl120)),
+        _ => unreachable!("{renderer:?} is not a GL renderer"),
+    }
+}
+
 #[cfg(feature = "glow-backend")]
 fn build_glow(
     video: &VideoSubsystem,
     make_window: &impl Fn(&VideoSubsystem, bool) -> Result<Window, String>,
-    profile: sdl2::video::GLProfile,
-    major: u8,
-    minor: u8,
+    renderer: Renderer,
+    transparent: bool,
 ) -> Result<Backend, String> {
+    let (profile, major, minor, shader_version) = gl_request(renderer);
     // Not `video.gl_attr()`: its setters panic on rejection, which would kill
     // the fallthrough on a device without GL.
     use sdl2::sys::SDL_GLattr::*;
@@ -450,6 +722,11 @@ fn build_glow(
     set_gl_attr("major_version", SDL_GL_CONTEXT_MAJOR_VERSION, major as i32)?;
     set_gl_attr("minor_version", SDL_GL_CONTEXT_MINOR_VERSION, minor as i32)?;
     set_gl_attr("doublebuffer", SDL_GL_DOUBLEBUFFER, 1)?;
+    // Set either way: the attribute outlives this window.
+    let alpha = if transparent { 8 } else { 0 };
+    if let Err(e) = set_gl_attr("alpha_size", SDL_GL_ALPHA_SIZE, alpha) {
+        log::warn!("the window stays opaque: {e}");
+    }
 
     let window = make_window(video, true)?;
     let gl_context = window.gl_create_context()?;
@@ -461,7 +738,14 @@ fn build_glow(
             video.gl_get_proc_address(name) as *const std::os::raw::c_void
         })
     });
-    let egui = crate::EguiGlow::new(&window, glow_ctx, None, false);
+    // A context that came up can still fail egui's shaders; that is this
+    // renderer being unavailable, not the app's end.
+    let mut egui = crate::EguiGlow::try_new(&window, glow_ctx, shader_version, false)
+        .map_err(|e| e.to_string())?;
+    if let Err(e) = probe_glow(&mut egui, window.drawable_size()) {
+        egui.destroy();
+        return Err(e);
+    }
     Ok(Backend::Glow {
         window,
         _gl_context: gl_context,
@@ -469,13 +753,142 @@ fn build_glow(
     })
 }
 
+/// Paint one green rect and read it back. Some drivers hand out a context,
+/// compile the shaders and then draw nothing, or garbage; this is where they
+/// count as unavailable. It paints into a small framebuffer of its own, as a
+/// hidden window's pixels may read back as anything; a GL 2.1 context with no
+/// framebuffer objects paints into the window instead. Nothing is presented.
+#[cfg(feature = "glow-backend")]
+fn probe_glow(egui: &mut crate::EguiGlow, (width, height): (u32, u32)) -> Result<(), String> {
+    use glow::HasContext as _;
+    const PROBE: TextureId = TextureId::User(u64::MAX);
+    const SIDE: u32 = 4;
+    let gl = egui.painter.gl().clone();
+    let version = gl.version();
+    let target = if version.is_embedded
+        || version.major >= 3
+        || gl
+            .supported_extensions()
+            .contains("GL_ARB_framebuffer_object")
+    {
+        Some(unsafe { probe_target(&gl, SIDE as i32) }?)
+    } else {
+        None
+    };
+    let size = match target {
+        Some(_) => [SIDE, SIDE],
+        None => [width.max(1), height.max(1)],
+    };
+
+    let white = ColorImage::new([1, 1], vec![Color32::WHITE]);
+    egui.painter
+        .set_texture(PROBE, &ImageDelta::full(white, TextureOptions::NEAREST));
+    let screen =
+        egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(size[0] as f32, size[1] as f32));
+    let mut mesh = egui::Mesh::with_texture(PROBE);
+    mesh.add_rect_with_uv(
+        screen,
+        egui::Rect::from_min_max(egui::Pos2::ZERO, egui::pos2(1.0, 1.0)),
+        Color32::GREEN,
+    );
+    let job = egui::ClippedPrimitive {
+        clip_rect: screen,
+        primitive: egui::epaint::Primitive::Mesh(mesh),
+    };
+    egui.painter.clear(size, [0.0, 0.0, 0.0, 1.0]);
+    egui.painter.paint_primitives(size, 1.0, &[job]);
+    let mut pixel = [0u8; 4];
+    unsafe {
+        gl.read_pixels(
+            0,
+            0,
+            1,
+            1,
+            glow::RGBA,
+            glow::UNSIGNED_BYTE,
+            glow::PixelPackData::Slice(Some(&mut pixel)),
+        );
+    }
+    egui.painter.free_texture(PROBE);
+    match target {
+        Some((framebuffer, texture)) => unsafe {
+            gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+            gl.delete_framebuffer(framebuffer);
+            gl.delete_texture(texture);
+        },
+        None => egui.painter.clear(size, [0.0, 0.0, 0.0, 1.0]),
+    }
+    match pixel {
+        [r, g, b, _] if r < 64 && g > 192 && b < 64 => Ok(()),
+        _ => Err(format!(
+            "a probe frame painted green read back as {pixel:?}"
+        )),
+    }
+}
+
+/// A `side` by `side` RGBA texture in a framebuffer, bound for drawing and
+/// reading.
+///
+/// # Safety
+/// `gl`'s context must be current, with framebuffer objects.
+#[cfg(feature = "glow-backend")]
+unsafe fn probe_target(
+    gl: &glow::Context,
+    side: i32,
+) -> Result<(glow::Framebuffer, glow::Texture), String> {
+    use glow::HasContext as _;
+    unsafe {
+        let texture = gl.create_texture()?;
+        gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+        gl.tex_parameter_i32(
+            glow::TEXTURE_2D,
+            glow::TEXTURE_MIN_FILTER,
+            glow::NEAREST as i32,
+        );
+        gl.tex_image_2d(
+            glow::TEXTURE_2D,
+            0,
+            glow::RGBA as i32,
+            side,
+            side,
+            0,
+            glow::RGBA,
+            glow::UNSIGNED_BYTE,
+            glow::PixelUnpackData::Slice(None),
+        );
+        gl.bind_texture(glow::TEXTURE_2D, None);
+        let framebuffer = match gl.create_framebuffer() {
+            Ok(framebuffer) => framebuffer,
+            Err(e) => {
+                gl.delete_texture(texture);
+                return Err(e);
+            }
+        };
+        gl.bind_framebuffer(glow::FRAMEBUFFER, Some(framebuffer));
+        gl.framebuffer_texture_2d(
+            glow::FRAMEBUFFER,
+            glow::COLOR_ATTACHMENT0,
+            glow::TEXTURE_2D,
+            Some(texture),
+            0,
+        );
+        let status = gl.check_framebuffer_status(glow::FRAMEBUFFER);
+        if status != glow::FRAMEBUFFER_COMPLETE {
+            gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+            gl.delete_framebuffer(framebuffer);
+            gl.delete_texture(texture);
+            return Err(format!("the probe framebuffer is incomplete ({status:#x})"));
+        }
+        Ok((framebuffer, texture))
+    }
+}
+
 #[cfg(not(feature = "glow-backend"))]
 fn build_glow(
     _video: &VideoSubsystem,
     _make_window: &impl Fn(&VideoSubsystem, bool) -> Result<Window, String>,
-    _profile: sdl2::video::GLProfile,
-    _major: u8,
-    _minor: u8,
+    _renderer: Renderer,
+    _transparent: bool,
 ) -> Result<Backend, String> {
     Err("built without the glow-backend feature".to_string())
 }
@@ -578,11 +991,17 @@ fn build_canvas(
 fn build_wgpu(
     video: &VideoSubsystem,
     make_window: &impl Fn(&VideoSubsystem, bool) -> Result<Window, String>,
+    transparent: bool,
 ) -> Result<Backend, String> {
     let window = make_window(video, false)?;
+    let options = crate::WgpuOptions {
+        transparent,
+        ..Default::default()
+    };
     // wgpu's setup is async; this is startup, so blocking on it is the whole
     // ceremony an app would otherwise write itself.
-    let egui = pollster::block_on(crate::EguiWgpu::new(window));
+    let egui = pollster::block_on(crate::EguiWgpu::with_options(window, options))
+        .map_err(|e| e.to_string())?;
     Ok(Backend::Wgpu {
         egui: Box::new(egui),
     })
@@ -592,6 +1011,7 @@ fn build_wgpu(
 fn build_wgpu(
     _video: &VideoSubsystem,
     _make_window: &impl Fn(&VideoSubsystem, bool) -> Result<Window, String>,
+    _transparent: bool,
 ) -> Result<Backend, String> {
     Err("built without the wgpu-backend feature".to_string())
 }
@@ -642,7 +1062,7 @@ fn paint_turned(
                 log::error!("could not build a {side}x{side} target to turn the frame in: {e}");
                 canvas.set_draw_color(rgb(clear_color));
                 canvas.clear();
-                egui.paint(canvas);
+                paint_canvas(egui, canvas, false);
                 canvas.present();
                 return;
             }
@@ -655,7 +1075,7 @@ fn paint_turned(
     let painted = canvas.with_texture_canvas(&mut target.texture, |target| {
         target.set_draw_color(rgb(clear_color));
         target.clear();
-        egui.paint(target);
+        paint_canvas(egui, target, true);
     });
     if let Err(e) = painted {
         return log::error!("could not paint into the turned target: {e}");
@@ -678,13 +1098,14 @@ fn paint_turned(
     );
     canvas.set_draw_color(rgb(clear_color));
     canvas.clear();
+    // SDL mirrors the source before it turns it, as `Rotation` does.
     let copied = canvas.copy_ex(
         &target.texture,
         Some(src),
         Some(dst),
         rotation.degrees(),
         None,
-        false,
+        rotation.is_flipped(),
         false,
     );
     if let Err(e) = copied {
@@ -721,6 +1142,10 @@ fn rotate_frame(
                 Rotation::Cw90 => (y, width - 1 - x),
                 Rotation::Cw180 => (width - 1 - x, height - 1 - y),
                 Rotation::Cw270 => (height - 1 - y, x),
+                Rotation::Flip => (width - 1 - x, y),
+                Rotation::FlipCw90 => (height - 1 - y, width - 1 - x),
+                Rotation::FlipCw180 => (x, height - 1 - y),
+                Rotation::FlipCw270 => (y, x),
             };
             let at = sy * pitch + sx * BYTES_PER_PIXEL;
             pixel.copy_from_slice(&src[at..at + BYTES_PER_PIXEL]);
@@ -728,6 +1153,37 @@ fn rotate_frame(
     }
 }
 
+/// [`crate::EguiCanvas::paint`], with a refused texture logged: the frame went
+/// out regardless, and the painter retries it by itself. `turned_at_present`
+/// says whether the frame on `canvas` is turned on its way to the window.
+#[cfg(feature = "canvas-backend")]
+fn paint_canvas<C, T: crate::canvas::CallbackTarget<Context = C>>(
+    egui: &mut crate::EguiCanvas<C>,
+    canvas: &mut sdl2::render::Canvas<T>,
+    turned_at_present: bool,
+) {
+    if let Err(e) = egui.paint_for(canvas, turned_at_present) {
+        log::warn!("{e}");
+    }
+}
+
+/// Copy an app's RGBA frame into `image`, which must be its size.
+fn fill_image(image: &mut ColorImage, rgba: &[u8]) -> Result<(), String> {
+    let expected = image.pixels.len() * 4;
+    if rgba.len() != expected {
+        return Err(format!(
+            "{} bytes of RGBA for a {}x{} texture, which takes {expected}",
+            rgba.len(),
+            image.size[0],
+            image.size[1]
+        ));
+    }
+    for (pixel, bytes) in image.pixels.iter_mut().zip(rgba.chunks_exact(4)) {
+        *pixel = Color32::from_rgba_premultiplied(bytes[0], bytes[1], bytes[2], bytes[3]);
+    }
+    Ok(())
+}
+
 /// egui and GL take linear floats; SDL clears in 8-bit channels.
 #[cfg(feature = "canvas-backend")]
 fn rgb(color: [f32; 4]) -> sdl2::pixels::Color {
@@ -780,6 +1236,14 @@ mod tests {
             .collect()
     }
 
+    #[test]
+    fn an_rgba_frame_must_fit_its_texture() {
+        let mut image = ColorImage::new([2, 1], vec![Color32::TRANSPARENT; 2]);
+        assert!(fill_image(&mut image, &[0; 4]).is_err());
+        fill_image(&mut image, &[1, 2, 3, 255, 4, 5, 6, 255]).expect("two pixels");
+        assert_eq!(image.pixels[1], Color32::from_rgb(4, 5, 6));
+    }
+
     #[test]
     fn an_unturned_frame_is_copied_across_as_it_is() {
         assert_eq!(presented(Rotation::None), [0, 1, 2, 10, 11, 12]);
@@ -801,4 +1265,30 @@ mod tests {
     fn a_quarter_turn_counterclockwise_is_the_other_way_round() {
         assert_eq!(presented(Rotation::Cw270), [1, 11, 21, 0, 10, 20]);
     }
+
+    #[test]
+    fn a_mirror_reverses_one_axis_before_the_turn() {
+        assert_eq!(presented(Rotation::Flip), [2, 1, 0, 12, 11, 10]);
+        assert_eq!(presented(Rotation::FlipCw180), [10, 11, 12, 0, 1, 2]);
+        // The 2 x 3 screen read down its columns.
+        assert_eq!(presented(Rotation::FlipCw270), [0, 10, 20, 1, 11, 21]);
+        assert_eq!(presented(Rotation::FlipCw90), [21, 11, 1, 20, 10, 0]);
+    }
+
+    #[test]
+    fn every_frame_comes_down_where_input_maps_back_from() {
+        // The blit and `Rotation::from_window` must agree pixel for pixel, or
+        // a click lands beside what it is on.
+        let window = egui::vec2(WINDOW.0 as f32, WINDOW.1 as f32);
+        for rotation in Rotation::ALL {
+            let frame = presented(rotation);
+            for (i, value) in frame.iter().enumerate() {
+                let (x, y) = (i % WINDOW.0 as usize, i / WINDOW.0 as usize);
+                let centre = egui::pos2(x as f32 + 0.5, y as f32 + 0.5);
+                let on_screen = rotation.from_window(centre, window);
+                let expected = on_screen.y as u8 * 10 + on_screen.x as u8;
+                assert_eq!(*value, expected, "{rotation:?} at {x},{y}");
+            }
+        }
+    }
 }
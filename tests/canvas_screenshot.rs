@@ -0,0 +1,61 @@
+//! A `ViewportCommand::Screenshot` on the canvas backend is answered on the
+//! next frame with an `Event::Screenshot` of the screen egui laid out: the
+//! window's size, or turned with it.
+#![cfg(feature = "canvas-backend")]
+
+use egui::{Event, ViewportCommand};
+use egui_sdl2::{EguiCanvas, Rotation};
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::render::Canvas;
+use sdl2::surface::Surface;
+
+const WIDTH: u32 = 64;
+const HEIGHT: u32 = 32;
+
+// One test, as SDL is only to be brought up on one thread.
+#[test]
+fn a_screenshot_comes_back_the_size_of_the_screen() {
+    if std::env::var_os("SDL_VIDEODRIVER").is_none() {
+        sdl2::hint::set("SDL_VIDEODRIVER", "dummy");
+    }
+    let sdl = sdl2::init().expect("SDL");
+    let video = sdl.video().expect("SDL video");
+    let window = video
+        .window("canvas screenshot", WIDTH, HEIGHT)
+        .hidden()
+        .build()
+        .expect("a window");
+    // Square, as the blit path's offscreen is, so a turned screen fits.
+    let side = WIDTH.max(HEIGHT);
+    let surface = Surface::new(side, side, PixelFormatEnum::ABGR8888).expect("a surface");
+    let mut canvas = Canvas::from_surface(surface).expect("SDL's software renderer");
+    let mut egui = EguiCanvas::for_surface(&window, &canvas);
+
+    let mut screenshot_size = |egui: &mut EguiCanvas<_>| {
+        egui.run(|ctx| ctx.send_viewport_cmd(ViewportCommand::Screenshot(Default::default())));
+        egui.paint(&mut canvas).expect("a frame");
+        let mut size = None;
+        egui.run(|ctx| {
+            ctx.input(|input| {
+                for event in &input.events {
+                    if let Event::Screenshot { image, .. } = event {
+                        size = Some(image.size);
+                    }
+                }
+            })
+        });
+        egui.paint(&mut canvas).expect("a frame");
+        size
+    };
+
+    assert_eq!(
+        screenshot_size(&mut egui),
+        Some([WIDTH as usize, HEIGHT as usize])
+    );
+    egui.state.set_rotation(Rotation::Cw90);
+    assert_eq!(
+        screenshot_size(&mut egui),
+        Some([HEIGHT as usize, WIDTH as usize])
+    );
+    egui.destroy();
+}
@@ -0,0 +1,531 @@
+//! How the canvas painter holds egui's textures: a refused one costs that image
+//! and not the frame, a texture budget evicts the least recently painted image
+//! and brings it back, an upload budget spreads a large one over frames, and an
+//! image past the renderer's limit is held and drawn as tiles. Paint callbacks
+//! draw in egui's order and leave the canvas as they found it, and the app's
+//! own textures are shown as they are, and its frames go up at once. egui's texture options pick the filter
+//! and the wrapping.
+#![cfg(feature = "canvas-backend")]
+
+use egui::epaint::{ImageDelta, Primitive};
+use egui::{
+    ClippedPrimitive, Color32, ColorImage, ImageData, Pos2, Rect, TextureId, TextureOptions,
+};
+use egui_sdl2::canvas::{CallbackCanvas, CallbackFn, PaintError, Painter};
+use sdl2::pixels::{Color, PixelFormatEnum};
+use sdl2::render::{Canvas, ClippingRect};
+use sdl2::surface::Surface;
+
+const SIDE: u32 = 64;
+const INK: [u8; 3] = [200, 100, 50];
+/// SDL refuses a texture with no pixels, on every driver.
+const REFUSED: TextureId = TextureId::User(1);
+const GOOD: TextureId = TextureId::User(2);
+
+fn canvas() -> Canvas<Surface<'static>> {
+    let surface = Surface::new(SIDE, SIDE, PixelFormatEnum::ABGR8888).expect("a surface");
+    Canvas::from_surface(surface).expect("SDL's software renderer")
+}
+
+fn ink(side: usize) -> ImageDelta {
+    filled(side, INK)
+}
+
+fn filled(side: usize, [r, g, b]: [u8; 3]) -> ImageDelta {
+    let image = ColorImage::new([side, side], vec![Color32::from_rgb(r, g, b); side * side]);
+    ImageDelta::full(image, TextureOptions::NEAREST)
+}
+
+fn screen() -> Rect {
+    Rect::from_min_size(Pos2::ZERO, egui::vec2(SIDE as f32, SIDE as f32))
+}
+
+/// `id` over the left half of the frame, `GOOD` over the right.
+fn halves(id: TextureId) -> Vec<ClippedPrimitive> {
+    let (left, right) = screen().split_left_right_at_fraction(0.5);
+    meshes(&[(id, left), (GOOD, right)])
+}
+
+/// Each texture drawn whole over its rect.
+fn meshes(images: &[(TextureId, Rect)]) -> Vec<ClippedPrimitive> {
+    let screen = screen();
+    let uv = Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0));
+    images
+        .iter()
+        .map(|&(id, rect)| {
+            let mut mesh = egui::Mesh::with_texture(id);
+            mesh.add_rect_with_uv(rect, uv, Color32::WHITE);
+            ClippedPrimitive {
+                clip_rect: screen,
+                primitive: Primitive::Mesh(mesh),
+            }
+        })
+        .collect()
+}
+
+fn pixel(canvas: &Canvas<Surface<'static>>, x: u32, y: u32) -> [u8; 3] {
+    let surface = canvas.surface();
+    let at = y as usize * surface.pitch() as usize + x as usize * 4;
+    let pixels = surface.without_lock().expect("a surface owns its pixels");
+    [pixels[at], pixels[at + 1], pixels[at + 2]]
+}
+
+fn paint(
+    painter: &mut Painter<sdl2::surface::SurfaceContext<'static>>,
+    canvas: &mut Canvas<Surface<'static>>,
+    deltas: Vec<(TextureId, ImageDelta)>,
+) -> Result<(), PaintError> {
+    paint_jobs(painter, canvas, deltas, halves(REFUSED))
+}
+
+fn paint_jobs(
+    painter: &mut Painter<sdl2::surface::SurfaceContext<'static>>,
+    canvas: &mut Canvas<Surface<'static>>,
+    deltas: Vec<(TextureId, ImageDelta)>,
+    jobs: Vec<ClippedPrimitive>,
+) -> Result<(), PaintError> {
+    let mut textures_delta = egui::TexturesDelta::default();
+    for (id, delta) in deltas {
+        textures_delta.push(id, delta);
+    }
+    canvas.set_draw_color(Color::RGB(0, 0, 0));
+    canvas.clear();
+    painter.paint_and_update_textures(canvas, 1.0, &mut textures_delta, jobs)
+}
+
+#[test]
+fn a_refused_texture_still_paints_the_rest_of_the_frame() {
+    let mut canvas = canvas();
+    let mut painter = Painter::for_surface(&canvas);
+
+    let result = paint(
+        &mut painter,
+        &mut canvas,
+        vec![(REFUSED, ink(0)), (GOOD, ink(2))],
+    );
+
+    let Err(PaintError::CreateTexture { id, .. }) = result else {
+        panic!("expected the empty texture to be refused, got {result:?}");
+    };
+    assert_eq!(id, REFUSED);
+    assert_eq!(painter.failed_textures().collect::<Vec<_>>(), [REFUSED]);
+    assert_eq!(pixel(&canvas, SIDE * 3 / 4, SIDE / 2), INK);
+    // Neither the image nor nothing: the placeholder says something is missing.
+    assert_eq!(pixel(&canvas, SIDE / 4, SIDE / 2), [255, 0, 255]);
+    painter.destroy();
+}
+
+#[test]
+fn a_whole_image_replaces_the_one_that_was_refused() {
+    let mut canvas = canvas();
+    let mut painter = Painter::for_surface(&canvas);
+    let _ = paint(
+        &mut painter,
+        &mut canvas,
+        vec![(REFUSED, ink(0)), (GOOD, ink(2))],
+    );
+
+    paint(&mut painter, &mut canvas, vec![(REFUSED, ink(2))]).expect("a texture SDL takes");
+
+    assert_eq!(painter.failed_textures().count(), 0);
+    assert_eq!(pixel(&canvas, SIDE / 4, SIDE / 2), INK);
+    painter.destroy();
+}
+
+#[test]
+fn the_least_recently_painted_image_makes_room() {
+    const A: TextureId = TextureId::User(10);
+    const B: TextureId = TextureId::User(11);
+    const C: TextureId = TextureId::User(12);
+    const TEAL: [u8; 3] = [0, 128, 128];
+    let image_bytes = 2 * 2 * 4;
+    let mut canvas = canvas();
+    let mut painter = Painter::for_surface(&canvas);
+    painter.set_texture_budget(Some(2 * image_bytes));
+    let whole = screen();
+
+    paint_jobs(
+        &mut painter,
+        &mut canvas,
+        vec![(A, filled(2, TEAL)), (B, ink(2))],
+        meshes(&[(A, whole), (B, whole)]),
+    )
+    .expect("two images fit");
+    paint_jobs(
+        &mut painter,
+        &mut canvas,
+        vec![(C, ink(2))],
+        meshes(&[(B, whole), (C, whole)]),
+    )
+    .expect("a third image fits once the first is out");
+    assert_eq!(painter.texture_bytes(), 2 * image_bytes);
+
+    // Shown again, it comes back from the copy it was evicted with.
+    paint_jobs(&mut painter, &mut canvas, vec![], meshes(&[(A, whole)]))
+        .expect("the evicted image is rebuilt");
+    assert_eq!(painter.texture_bytes(), 2 * image_bytes);
+    assert_eq!(pixel(&canvas, SIDE / 2, SIDE / 2), TEAL);
+    painter.destroy();
+}
+
+#[test]
+fn an_image_with_a_patch_on_its_way_is_not_evicted() {
+    const A: TextureId = TextureId::User(13);
+    const B: TextureId = TextureId::User(14);
+    const TEAL: [u8; 3] = [0, 128, 128];
+    let mut canvas = canvas();
+    let mut painter = Painter::for_surface(&canvas);
+    painter.set_texture_budget(Some(2 * 4 * 4 * 4));
+    let whole = screen();
+    paint_jobs(
+        &mut painter,
+        &mut canvas,
+        vec![(A, ink(4)), (B, ink(4))],
+        meshes(&[(A, whole), (B, whole)]),
+    )
+    .expect("two images fit");
+
+    // Room for one: A is the least recently painted, but its patch goes up a
+    // row a frame and is still on its way when the frame ends.
+    painter.set_texture_budget(Some(4 * 4 * 4));
+    painter.set_upload_budget(Some(4 * 4));
+    let mut patch = filled(4, TEAL);
+    patch.pos = Some([0, 0]);
+    let mut deltas = vec![(A, patch)];
+    while !deltas.is_empty() || painter.has_pending_uploads() {
+        paint_jobs(
+            &mut painter,
+            &mut canvas,
+            std::mem::take(&mut deltas),
+            meshes(&[(B, whole)]),
+        )
+        .expect("every row finds its texture");
+    }
+
+    // Evicted once the patch was in, and back with it.
+    loop {
+        paint_jobs(&mut painter, &mut canvas, vec![], meshes(&[(A, whole)])).expect("A shown");
+        if !painter.has_pending_uploads() {
+            break;
+        }
+    }
+    assert_eq!(pixel(&canvas, SIDE / 2, SIDE / 2), TEAL);
+    painter.destroy();
+}
+
+#[test]
+fn a_large_image_goes_up_over_several_frames() {
+    const IMAGE: TextureId = TextureId::User(20);
+    let mut canvas = canvas();
+    let mut painter = Painter::for_surface(&canvas);
+    // One row of a 4x4 image a frame.
+    painter.set_upload_budget(Some(4 * 4));
+    let jobs = || meshes(&[(IMAGE, screen())]);
+
+    paint_jobs(&mut painter, &mut canvas, vec![(IMAGE, ink(4))], jobs()).expect("queued");
+    for _ in 0..2 {
+        assert!(painter.has_pending_uploads());
+        // Half an image is not drawn: what is in the rest is anyone's guess.
+        assert_eq!(pixel(&canvas, SIDE / 2, SIDE / 2), [0, 0, 0]);
+        paint_jobs(&mut painter, &mut canvas, vec![], jobs()).expect("another row");
+    }
+    assert!(painter.has_pending_uploads());
+    paint_jobs(&mut painter, &mut canvas, vec![], jobs()).expect("the last row");
+
+    assert!(!painter.has_pending_uploads());
+    assert_eq!(pixel(&canvas, SIDE / 2, SIDE / 2), INK);
+    painter.destroy();
+}
+
+/// A 4x4 image in four colours, a 2x2 block each.
+fn quadrants() -> ImageDelta {
+    const COLOURS: [[u8; 3]; 4] = [[255, 0, 0], [0, 255, 0], [0, 0, 255], INK];
+    let pixels = (0..16)
+        .map(|i| {
+            let (x, y) = (i % 4, i / 4);
+            let [r, g, b] = COLOURS[(y / 2) * 2 + x / 2];
+            Color32::from_rgb(r, g, b)
+        })
+        .collect();
+    ImageDelta::full(ColorImage::new([4, 4], pixels), TextureOptions::NEAREST)
+}
+
+/// `id` over the whole frame as four triangles around its centre, which no blit
+/// can draw.
+fn fan(id: TextureId) -> Vec<ClippedPrimitive> {
+    let screen = screen();
+    let mut mesh = egui::Mesh::with_texture(id);
+    let corners = [
+        screen.left_top(),
+        screen.right_top(),
+        screen.right_bottom(),
+        screen.left_bottom(),
+    ];
+    let uv = |p: Pos2| Pos2::new(p.x / screen.width(), p.y / screen.height());
+    for p in std::iter::once(screen.center()).chain(corners) {
+        mesh.vertices.push(egui::epaint::Vertex {
+            pos: p,
+            uv: uv(p),
+            color: Color32::WHITE,
+        });
+    }
+    mesh.indices = vec![0, 1, 2, 0, 2, 3, 0, 3, 4, 0, 4, 1];
+    vec![ClippedPrimitive {
+        clip_rect: screen,
+        primitive: Primitive::Mesh(mesh),
+    }]
+}
+
+#[test]
+fn an_image_past_the_limit_is_drawn_from_tiles() {
+    const IMAGE: TextureId = TextureId::User(30);
+    const TEAL: [u8; 3] = [0, 128, 128];
+    let mut canvas = canvas();
+    let mut painter = Painter::for_surface(&canvas);
+    painter.set_max_texture_side(Some(2));
+    let quarter = SIDE / 4;
+    let corners = [
+        (quarter, quarter),
+        (SIDE - quarter, quarter),
+        (quarter, SIDE - quarter),
+        (SIDE - quarter, SIDE - quarter),
+    ];
+    let expected = [[255, 0, 0], [0, 255, 0], [0, 0, 255], INK];
+
+    for jobs in [meshes(&[(IMAGE, screen())]), fan(IMAGE)] {
+        paint_jobs(&mut painter, &mut canvas, vec![(IMAGE, quadrants())], jobs)
+            .expect("four tiles SDL takes");
+        for ((x, y), colour) in corners.into_iter().zip(expected) {
+            assert_eq!(pixel(&canvas, x, y), colour, "at {x},{y}");
+        }
+    }
+
+    // A patch over the middle lands in all four tiles.
+    let patch = ColorImage::new([2, 2], vec![Color32::from_rgb(0, 128, 128); 4]);
+    let patch = ImageDelta::partial([1, 1], patch, TextureOptions::NEAREST);
+    paint_jobs(
+        &mut painter,
+        &mut canvas,
+        vec![(IMAGE, patch)],
+        meshes(&[(IMAGE, screen())]),
+    )
+    .expect("a patch across the seams");
+    assert_eq!(pixel(&canvas, SIDE / 2, SIDE / 2), TEAL);
+    assert_eq!(pixel(&canvas, SIDE / 2 - 1, SIDE / 2 - 1), TEAL);
+    assert_eq!(pixel(&canvas, SIDE / 8, SIDE / 8), [255, 0, 0]);
+    painter.destroy();
+}
+
+#[test]
+fn a_paint_callback_draws_between_meshes_within_its_clip() {
+    const RED: [u8; 3] = [255, 0, 0];
+    let mut canvas = canvas();
+    let mut painter = Painter::for_surface(&canvas);
+    let callback = CallbackFn::new(|info, canvas| {
+        let CallbackCanvas::Surface(canvas) = canvas else {
+            panic!("painting into a surface");
+        };
+        assert_eq!(canvas.clip_rect(), ClippingRect::Some(info.clip_rect));
+        canvas.set_blend_mode(sdl2::render::BlendMode::None);
+        canvas.set_draw_color(Color::RGB(255, 0, 0));
+        canvas.fill_rect(None).expect("a fill");
+    });
+    let (left, _) = screen().split_left_right_at_fraction(0.5);
+    let (top_left, _) = left.split_top_bottom_at_fraction(0.5);
+    let mut jobs = meshes(&[(GOOD, screen())]);
+    jobs.push(ClippedPrimitive {
+        clip_rect: left,
+        primitive: Primitive::Callback(egui::PaintCallback {
+            rect: screen(),
+            callback: std::sync::Arc::new(callback),
+        }),
+    });
+    jobs.extend(meshes(&[(GOOD, top_left)]));
+
+    paint_jobs(&mut painter, &mut canvas, vec![(GOOD, ink(2))], jobs).expect("a texture SDL takes");
+
+    assert_eq!(pixel(&canvas, SIDE / 4, SIDE / 4), INK);
+    assert_eq!(pixel(&canvas, SIDE / 4, SIDE * 3 / 4), RED);
+    assert_eq!(pixel(&canvas, SIDE * 3 / 4, SIDE * 3 / 4), INK);
+    assert_eq!(canvas.draw_color(), Color::RGB(0, 0, 0));
+    assert_eq!(canvas.clip_rect(), ClippingRect::None);
+    painter.destroy();
+}
+
+#[test]
+fn a_surface_over_the_apps_own_pixels_is_painted_into() {
+    const RED: [u8; 3] = [255, 0, 0];
+    let mut pixels = vec![0u8; (SIDE * SIDE * 4) as usize];
+    {
+        let surface =
+            Surface::from_data(&mut pixels, SIDE, SIDE, SIDE * 4, PixelFormatEnum::ABGR8888)
+                .expect("a surface over a buffer");
+        let mut canvas = Canvas::from_surface(surface).expect("SDL's software renderer");
+        let mut painter = Painter::for_surface(&canvas);
+        let callback = CallbackFn::new(|_, canvas| {
+            let CallbackCanvas::Surface(canvas) = canvas else {
+                panic!("painting into a surface");
+            };
+            canvas.set_draw_color(Color::RGB(255, 0, 0));
+            canvas.fill_rect(None).expect("a fill");
+        });
+        let jobs = vec![ClippedPrimitive {
+            clip_rect: screen(),
+            primitive: Primitive::Callback(egui::PaintCallback {
+                rect: screen(),
+                callback: std::sync::Arc::new(callback),
+            }),
+        }];
+        painter
+            .paint_and_update_textures(&mut canvas, 1.0, &mut Default::default(), jobs)
+            .expect("nothing to upload");
+        painter.destroy();
+    }
+    assert_eq!(pixels[..3], RED);
+}
+
+#[test]
+fn an_app_texture_is_shown_as_it_is() {
+    const TEAL: [u8; 3] = [0, 128, 128];
+    let mut canvas = canvas();
+    let mut painter = Painter::for_surface(&canvas);
+    let creator = canvas.texture_creator();
+    let app_texture = |[r, g, b]: [u8; 3]| {
+        let mut texture = creator
+            .create_texture_static(PixelFormatEnum::ABGR8888, 2, 2)
+            .expect("a texture");
+        texture
+            .update(None, &[r, g, b, 255].repeat(4), 8)
+            .expect("its pixels");
+        texture
+    };
+
+    // Numbered clear of the ids the app gives egui images of its own.
+    const OWN: TextureId = TextureId::User(0);
+    let id = painter.register_native_texture(app_texture(TEAL));
+    assert_ne!(id, OWN);
+    let (left, right) = screen().split_left_right_at_fraction(0.5);
+    paint_jobs(
+        &mut painter,
+        &mut canvas,
+        vec![(OWN, ink(2))],
+        meshes(&[(OWN, left), (id, right)]),
+    )
+    .expect("the app's own image");
+    assert_eq!(pixel(&canvas, SIDE / 4, SIDE / 2), INK);
+    assert_eq!(pixel(&canvas, SIDE * 3 / 4, SIDE / 2), TEAL);
+    painter.free_texture(&OWN);
+
+    painter.replace_native_texture(id, app_texture(INK));
+    paint_jobs(&mut painter, &mut canvas, vec![], meshes(&[(id, screen())])).expect("no uploads");
+    assert_eq!(pixel(&canvas, SIDE / 2, SIDE / 2), INK);
+    assert_eq!(painter.texture_bytes(), 0);
+    painter.destroy();
+}
+
+#[test]
+fn an_app_frame_goes_up_at_once_and_is_not_kept() {
+    let mut canvas = canvas();
+    let mut painter = Painter::for_surface(&canvas);
+    // Budgets that would queue the frame and keep a copy of it for eviction.
+    painter.set_upload_budget(Some(1));
+    painter.set_texture_budget(Some(1));
+    let frame = ink(SIDE as usize);
+    painter
+        .set_texture_now(GOOD, &frame)
+        .expect("a texture SDL takes");
+    assert!(!painter.has_pending_uploads());
+    let ImageData::Color(pixels) = &frame.image;
+    assert_eq!(std::sync::Arc::strong_count(pixels), 1, "a copy was kept");
+
+    paint_jobs(
+        &mut painter,
+        &mut canvas,
+        vec![],
+        meshes(&[(GOOD, screen())]),
+    )
+    .expect("no uploads");
+    assert_eq!(pixel(&canvas, SIDE / 2, SIDE / 2), INK);
+    painter.destroy();
+}
+
+/// `id` over the whole frame, sampled `repeats` times across and down.
+fn repeated(id: TextureId, repeats: f32) -> Vec<ClippedPrimitive> {
+    let mut mesh = egui::Mesh::with_texture(id);
+    let uv = Rect::from_min_max(Pos2::ZERO, Pos2::new(repeats, repeats));
+    mesh.add_rect_with_uv(screen(), uv, Color32::WHITE);
+    vec![ClippedPrimitive {
+        clip_rect: screen(),
+        primitive: Primitive::Mesh(mesh),
+    }]
+}
+
+#[test]
+fn a_texture_is_filtered_as_egui_asks() {
+    const IMAGE: TextureId = TextureId::User(40);
+    let mut canvas = canvas();
+    let mut painter = Painter::for_surface(&canvas);
+    let stripes = |options| {
+        let pixels = vec![Color32::BLACK, Color32::WHITE];
+        ImageDelta::full(ColorImage::new([2, 1], pixels), options)
+    };
+    let middle = |canvas: &Canvas<Surface<'static>>| pixel(canvas, SIDE / 2, SIDE / 2)[0];
+    let jobs = || meshes(&[(IMAGE, screen())]);
+
+    paint_jobs(
+        &mut painter,
+        &mut canvas,
+        vec![(IMAGE, stripes(TextureOptions::NEAREST))],
+        jobs(),
+    )
+    .expect("a texture SDL takes");
+    assert!(matches!(middle(&canvas), 0 | 255));
+
+    // Same size, new options: the texture stays and is filtered anew.
+    paint_jobs(
+        &mut painter,
+        &mut canvas,
+        vec![(IMAGE, stripes(TextureOptions::LINEAR))],
+        jobs(),
+    )
+    .expect("a texture SDL takes");
+    assert!((64..192).contains(&middle(&canvas)), "{}", middle(&canvas));
+    painter.destroy();
+}
+
+#[test]
+fn a_repeated_texture_is_drawn_once_per_repeat() {
+    const IMAGE: TextureId = TextureId::User(41);
+    const RED: [u8; 3] = [255, 0, 0];
+    const GREEN: [u8; 3] = [0, 255, 0];
+    let mut canvas = canvas();
+    let mut painter = Painter::for_surface(&canvas);
+    let with_wrap = |wrap_mode| {
+        let mut delta = quadrants();
+        delta.options.wrap_mode = wrap_mode;
+        delta
+    };
+    // A quarter into the second repeat across, in the first one down.
+    let (x, y) = (SIDE * 5 / 8, SIDE / 8);
+
+    paint_jobs(
+        &mut painter,
+        &mut canvas,
+        vec![(IMAGE, with_wrap(egui::TextureWrapMode::Repeat))],
+        repeated(IMAGE, 2.0),
+    )
+    .expect("a texture SDL takes");
+    assert_eq!(pixel(&canvas, x, y), RED);
+    assert_eq!(pixel(&canvas, SIDE / 8, SIDE / 8), RED);
+
+    // Mirrored, the second repeat starts from the far side.
+    paint_jobs(
+        &mut painter,
+        &mut canvas,
+        vec![(IMAGE, with_wrap(egui::TextureWrapMode::MirroredRepeat))],
+        repeated(IMAGE, 2.0),
+    )
+    .expect("a texture SDL takes");
+    assert_eq!(pixel(&canvas, x, y), GREEN);
+    painter.destroy();
+}
@@ -0,0 +1,113 @@
+//! Cutting from a text field puts the selection on the clipboard, with the cut
+//! ahead of the key that asked for it. Off Windows, Shift+Delete is no cut but
+//! the plain delete egui's text fields make of it, and the clipboard keeps what
+//! was cut before.
+#![cfg(target_os = "linux")]
+
+use egui::text::{CCursor, CCursorRange};
+use egui::{Event, Key};
+use egui_sdl2::State;
+use sdl2::event::Event as SdlEvent;
+use sdl2::keyboard::{Keycode, Mod, Scancode};
+
+fn key_down(
+    window: &sdl2::video::Window,
+    key: Keycode,
+    scancode: Scancode,
+    keymod: Mod,
+) -> SdlEvent {
+    SdlEvent::KeyDown {
+        timestamp: 0,
+        window_id: window.id(),
+        keycode: Some(key),
+        scancode: Some(scancode),
+        keymod,
+        repeat: false,
+    }
+}
+
+// One test, as SDL is only to be brought up on one thread.
+#[test]
+fn a_cut_reaches_the_clipboard_and_shift_delete_only_deletes() {
+    if std::env::var_os("SDL_VIDEODRIVER").is_none() {
+        sdl2::hint::set("SDL_VIDEODRIVER", "dummy");
+    }
+    let sdl = sdl2::init().expect("SDL");
+    let video = sdl.video().expect("SDL video");
+    let window = video
+        .window("clipboard cut", 200, 100)
+        .hidden()
+        .build()
+        .expect("a window");
+    let ctx = egui::Context::default();
+    let mut state = State::new(&window, ctx.clone(), egui::ViewportId::ROOT);
+    let mut text = "hello world".to_owned();
+
+    // A frame with the field focused and `select` chosen, then one that takes
+    // `events` in; what the field asked to copy goes to the clipboard.
+    let run =
+        |state: &mut State, text: &mut String, select: (usize, usize), events: &[SdlEvent]| {
+            for pass in 0..2 {
+                if pass == 1 {
+                    for event in events {
+                        let _ = state.on_event(&window, event);
+                    }
+                }
+                let input = state.take_egui_input();
+                let seen = input.events.clone();
+                let mut output = ctx.run_ui(input, |ui| {
+                    let mut edit = egui::TextEdit::singleline(text).show(ui);
+                    edit.response.request_focus();
+                    if pass == 0 {
+                        let range =
+                            CCursorRange::two(CCursor::new(select.0), CCursor::new(select.1));
+                        edit.state.cursor.set_char_range(Some(range));
+                        edit.state.store(ui.ctx(), edit.response.id);
+                    }
+                });
+                // egui 0.36 asserts that every texture delta is handled.
+                output.textures_delta.clear();
+                state.handle_platform_output(output.platform_output);
+                if pass == 1 {
+                    return seen;
+                }
+            }
+            unreachable!()
+        };
+
+    let cut = run(
+        &mut state,
+        &mut text,
+        (0, 5),
+        &[key_down(&window, Keycode::X, Scancode::X, Mod::LCTRLMOD)],
+    );
+    let cut_at = cut.iter().position(|event| *event == Event::Cut);
+    let key_at = cut.iter().position(|event| {
+        matches!(
+            event,
+            Event::Key {
+                key: Key::X,
+                pressed: true,
+                ..
+            }
+        )
+    });
+    assert!(cut_at.is_some() && cut_at < key_at, "{cut:?}");
+    assert_eq!(text, " world");
+    assert_eq!(video.clipboard().clipboard_text().as_deref(), Ok("hello"));
+
+    let deleted = run(
+        &mut state,
+        &mut text,
+        (1, 6),
+        &[key_down(
+            &window,
+            Keycode::Delete,
+            Scancode::Delete,
+            Mod::LSHIFTMOD,
+        )],
+    );
+    assert!(!deleted.contains(&Event::Cut), "{deleted:?}");
+    assert_eq!(text, " ");
+    assert_eq!(video.clipboard().clipboard_text().as_deref(), Ok("hello"));
+}
@@ -0,0 +1,86 @@
+//! What `GlState` captures, `restore` puts back, vertex array included where the
+//! context has one, and `EguiGlow` keeps the texture bound on the app's active
+//! unit through a frame that uploads one of its own. It needs a real GL
+//! context, which a headless machine may not offer; without one the test says
+//! so and passes.
+#![cfg(feature = "glow-backend")]
+
+use egui_sdl2::egui_glow::glow::{self, HasContext as _};
+use egui_sdl2::{EguiGlow, GlState};
+use std::sync::Arc;
+
+// One test, as SDL is only to be brought up on one thread.
+#[test]
+fn gl_state_puts_back_what_it_captured() {
+    let sdl = sdl2::init().expect("SDL");
+    let video = sdl.video().expect("SDL video");
+    let Ok(window) = video.window("gl state", 64, 64).opengl().hidden().build() else {
+        eprintln!("skipped: no GL window on this video driver");
+        return;
+    };
+    let Ok(_context) = window.gl_create_context() else {
+        eprintln!("skipped: no GL context on this video driver");
+        return;
+    };
+    let gl = Arc::new(unsafe {
+        glow::Context::from_loader_function(|name| {
+            video.gl_get_proc_address(name) as *const std::os::raw::c_void
+        })
+    });
+
+    unsafe {
+        // What an app might leave bound: its own vertex array, when there are
+        // any, and an element buffer that belongs to it.
+        let vertex_array = gl.create_vertex_array().ok();
+        if vertex_array.is_some() {
+            gl.bind_vertex_array(vertex_array);
+        }
+        let elements = gl.create_buffer().expect("a buffer");
+        gl.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, Some(elements));
+        gl.enable(glow::DEPTH_TEST);
+        gl.viewport(1, 2, 30, 40);
+        gl.clear_color(0.25, 0.5, 0.75, 1.0);
+        let saved = GlState::capture(&gl);
+
+        // What painting might leave instead.
+        if vertex_array.is_some() {
+            let other = gl.create_vertex_array().expect("another vertex array");
+            gl.bind_vertex_array(Some(other));
+        }
+        let other_elements = gl.create_buffer().expect("another buffer");
+        gl.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, Some(other_elements));
+        gl.disable(glow::DEPTH_TEST);
+        gl.enable(glow::BLEND);
+        gl.viewport(0, 0, 64, 64);
+        gl.clear_color(0.0, 0.0, 0.0, 0.0);
+
+        saved.restore(&gl);
+        assert_eq!(GlState::capture(&gl), saved);
+    }
+
+    // egui_glow uploads on whatever unit is active, before it turns to unit 0
+    // to paint; the first frame uploads the font atlas.
+    let app_texture = unsafe {
+        let texture = gl.create_texture().expect("a texture");
+        gl.active_texture(glow::TEXTURE3);
+        gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+        texture
+    };
+    let mut egui = EguiGlow::try_new(&window, gl.clone(), None, false).expect("egui_glow");
+    egui.set_preserve_gl_state(true);
+    egui.run_ui(|ui| {
+        ui.label("a frame with a texture to upload");
+    });
+    egui.paint();
+    unsafe {
+        assert_eq!(
+            gl.get_parameter_i32(glow::ACTIVE_TEXTURE) as u32,
+            glow::TEXTURE3
+        );
+        assert_eq!(
+            gl.get_parameter_texture(glow::TEXTURE_BINDING_2D),
+            Some(app_texture)
+        );
+    }
+    egui.destroy();
+}
@@ -0,0 +1,36 @@
+//! `EguiWindow` takes a GL renderer that paints, found by painting a probe
+//! frame off the window, which stays hidden. It needs a real GL context, which
+//! a headless machine may not offer; without one the test says so and passes.
+#![cfg(feature = "glow-backend")]
+
+use egui_sdl2::{EguiWindow, Renderer};
+
+// One test, as SDL is only to be brought up on one thread.
+#[test]
+fn a_hidden_window_passes_the_gl_probe() {
+    let sdl = sdl2::init().expect("SDL");
+    let video = sdl.video().expect("SDL video");
+    let order = [
+        Renderer::Gles3,
+        Renderer::Gl32,
+        Renderer::Gles2,
+        Renderer::Gl21,
+    ];
+    // A window that cannot have GL at all is not the probe's to judge.
+    if video.window("gl", 1, 1).opengl().hidden().build().is_err() {
+        eprintln!("skipped: no GL window on this video driver");
+        return;
+    }
+
+    let window = EguiWindow::new(
+        &video,
+        "glow probe",
+        (64, 64),
+        |builder| {
+            builder.hidden();
+        },
+        &order,
+    )
+    .expect("a GL renderer that paints");
+    assert!(order.contains(&window.renderer()));
+}
@@ -0,0 +1,76 @@
+//! A middle click in the focused text field pastes the primary selection there:
+//! the middle press, a primary click that moves the text cursor to it, and then
+//! the text, in that order.
+#![cfg(target_os = "linux")]
+
+use egui::{Event, PointerButton};
+use egui_sdl2::State;
+use sdl2::event::Event as SdlEvent;
+use sdl2::mouse::MouseButton;
+
+// One test, as SDL is only to be brought up on one thread.
+#[test]
+fn a_middle_click_in_the_focused_field_pastes_the_primary_selection() {
+    if std::env::var_os("SDL_VIDEODRIVER").is_none() {
+        sdl2::hint::set("SDL_VIDEODRIVER", "dummy");
+    }
+    let sdl = sdl2::init().expect("SDL");
+    let video = sdl.video().expect("SDL video");
+    let window = video
+        .window("primary paste", 200, 100)
+        .hidden()
+        .build()
+        .expect("a window");
+    let ctx = egui::Context::default();
+    let mut state = State::new(&window, ctx.clone(), egui::ViewportId::ROOT);
+    state.set_primary_selection(true);
+
+    // Two frames: one to focus the field, one for egui to know where it is.
+    let mut text = String::new();
+    let mut field = egui::Rect::NOTHING;
+    for _ in 0..2 {
+        let mut output = ctx.run_ui(state.take_egui_input(), |ui| {
+            let response = ui.text_edit_singleline(&mut text);
+            response.request_focus();
+            field = response.rect;
+        });
+        // egui 0.36 asserts that every texture delta is handled.
+        output.textures_delta.clear();
+    }
+
+    video
+        .clipboard()
+        .set_primary_selection_text("selected\r\nelsewhere")
+        .expect("a primary selection");
+    let at = field.center();
+    let _ = state.on_event(
+        &window,
+        &SdlEvent::MouseButtonDown {
+            timestamp: 0,
+            window_id: window.id(),
+            which: 0,
+            mouse_btn: MouseButton::Middle,
+            clicks: 1,
+            x: at.x as i32,
+            y: at.y as i32,
+        },
+    );
+
+    let events: Vec<Event> = state
+        .take_egui_input()
+        .events
+        .into_iter()
+        .filter(|event| matches!(event, Event::PointerButton { .. } | Event::Paste(_)))
+        .collect();
+    let button = |event: &Event| match event {
+        Event::PointerButton {
+            button, pressed, ..
+        } => Some((*button, *pressed)),
+        _ => None,
+    };
+    assert_eq!(events.len(), 4, "{events:?}");
+    assert_eq!(button(&events[0]), Some((PointerButton::Middle, true)));
+    assert_eq!(button(&events[1]), Some((PointerButton::Primary, true)));
+    assert_eq!(button(&events[2]), Some((PointerButton::Primary, false)));
+    assert_eq!(events[3], Event::Paste("selected\nelsewhere".to_owned()));
+}
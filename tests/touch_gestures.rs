@@ -0,0 +1,168 @@
+//! What `State` makes of finger events once touch gestures are on: a tap is a
+//! primary press and release, a finger held still is a secondary click, and
+//! two fingers moving together scroll. The raw touches go through as they came.
+
+use egui::{Event, MouseWheelUnit, PointerButton, TouchPhase};
+use egui_sdl2::{State, TouchGestures};
+use sdl2::event::Event as SdlEvent;
+use std::time::Duration;
+
+const SIDE: u32 = 100;
+
+/// A finger event at `(x, y)`, normalised to the window as SDL sends it.
+fn finger(phase: TouchPhase, finger_id: i64, x: f32, y: f32) -> SdlEvent {
+    let (timestamp, touch_id, dx, dy, pressure) = (0, 1, 0.0, 0.0, 1.0);
+    match phase {
+        TouchPhase::Start => SdlEvent::FingerDown {
+            timestamp,
+            touch_id,
+            finger_id,
+            x,
+            y,
+            dx,
+            dy,
+            pressure,
+        },
+        TouchPhase::Move => SdlEvent::FingerMotion {
+            timestamp,
+            touch_id,
+            finger_id,
+            x,
+            y,
+            dx,
+            dy,
+            pressure,
+        },
+        TouchPhase::End | TouchPhase::Cancel => SdlEvent::FingerUp {
+            timestamp,
+            touch_id,
+            finger_id,
+            x,
+            y,
+            dx,
+            dy,
+            pressure,
+        },
+    }
+}
+
+/// The buttons egui was told about, in order.
+fn buttons(events: &[Event]) -> Vec<(PointerButton, bool)> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            Event::PointerButton {
+                button, pressed, ..
+            } => Some((*button, *pressed)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The phases of the raw touches egui was given, in order.
+fn touches(events: &[Event]) -> Vec<TouchPhase> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            Event::Touch { phase, .. } => Some(*phase),
+            _ => None,
+        })
+        .collect()
+}
+
+// One test, as SDL is only to be brought up on one thread.
+#[test]
+fn taps_long_presses_and_two_finger_pans_are_told_apart() {
+    if std::env::var_os("SDL_VIDEODRIVER").is_none() {
+        sdl2::hint::set("SDL_VIDEODRIVER", "dummy");
+    }
+    let sdl = sdl2::init().expect("SDL");
+    let video = sdl.video().expect("SDL video");
+    let window = video
+        .window("touch gestures", SIDE, SIDE)
+        .hidden()
+        .build()
+        .expect("a window");
+    let mut state = State::new(&window, egui::Context::default(), egui::ViewportId::ROOT);
+    assert_eq!(state.touch_gestures(), &TouchGestures::none());
+    let long_press = Duration::from_millis(50);
+    state.set_touch_gestures(TouchGestures {
+        long_press: Some(long_press),
+        ..TouchGestures::default()
+    });
+    let feed = |state: &mut State, events: &[SdlEvent]| {
+        for event in events {
+            let _ = state.on_event(&window, event);
+        }
+        state.take_egui_input().events
+    };
+
+    // A tap: nothing on the way down, then the whole click as it lifts.
+    let down = feed(&mut state, &[finger(TouchPhase::Start, 1, 0.5, 0.5)]);
+    assert_eq!(buttons(&down), vec![]);
+    let up = feed(&mut state, &[finger(TouchPhase::End, 1, 0.5, 0.5)]);
+    assert_eq!(
+        buttons(&up),
+        vec![
+            (PointerButton::Primary, true),
+            (PointerButton::Primary, false)
+        ]
+    );
+
+    // A finger held still past the long press: a secondary click, and no
+    // primary one when it lifts.
+    let down = feed(&mut state, &[finger(TouchPhase::Start, 2, 0.5, 0.5)]);
+    assert_eq!(buttons(&down), vec![]);
+    std::thread::sleep(long_press + Duration::from_millis(20));
+    let held = feed(&mut state, &[]);
+    assert_eq!(
+        buttons(&held),
+        vec![
+            (PointerButton::Secondary, true),
+            (PointerButton::Secondary, false)
+        ]
+    );
+    let up = feed(&mut state, &[finger(TouchPhase::End, 2, 0.5, 0.5)]);
+    assert_eq!(buttons(&up), vec![]);
+
+    // Two fingers moving down together: a wheel in points, and every touch
+    // passed on as it was.
+    let pan = [
+        finger(TouchPhase::Start, 3, 0.4, 0.4),
+        finger(TouchPhase::Start, 4, 0.6, 0.4),
+        finger(TouchPhase::Move, 3, 0.4, 0.5),
+        finger(TouchPhase::Move, 4, 0.6, 0.5),
+        finger(TouchPhase::End, 3, 0.4, 0.5),
+        finger(TouchPhase::End, 4, 0.6, 0.5),
+    ];
+    let events = feed(&mut state, &pan);
+    assert_eq!(
+        touches(&events),
+        vec![
+            TouchPhase::Start,
+            TouchPhase::Start,
+            TouchPhase::Move,
+            TouchPhase::Move,
+            TouchPhase::End,
+            TouchPhase::End
+        ]
+    );
+    let scrolled = events
+        .iter()
+        .filter_map(|event| match event {
+            Event::MouseWheel { unit, delta, .. } => {
+                assert_eq!(*unit, MouseWheelUnit::Point);
+                Some(*delta)
+            }
+            _ => None,
+        })
+        .fold(egui::Vec2::ZERO, |sum, delta| sum + delta);
+    assert!(
+        (scrolled - egui::vec2(0.0, SIDE as f32 / 10.0)).length() < 0.01,
+        "{scrolled:?}"
+    );
+    assert!(
+        !buttons(&events).contains(&(PointerButton::Primary, true)),
+        "a pan is no click: {events:?}"
+    );
+}
@@ -0,0 +1,161 @@
+//! A tap reaches `State` twice: as the touch, and as the mouse events SDL makes
+//! up from it. The made-up ones are dropped unless asked for, so a tap presses
+//! once and the pointer stays on the touch's path. A device that does not drive
+//! the pointer keeps its made-up mouse events, and so does an app that does not
+//! forward finger events at all.
+
+use egui::{Event, PointerButton, Pos2};
+use egui_sdl2::{State, TouchHandling};
+use sdl2::event::Event as SdlEvent;
+use sdl2::mouse::{MouseButton, MouseState};
+
+/// `which` on the mouse events SDL synthesizes from touches.
+const SDL_TOUCH_MOUSEID: u32 = u32::MAX;
+const SIDE: u32 = 100;
+
+/// A tap at the middle of the window, as SDL sends it with its touch mouse
+/// events on: each mouse event right before the touch it stands for.
+fn tap() -> Vec<SdlEvent> {
+    let finger = |down: bool| {
+        let (x, y, dx, dy, pressure) = (0.5, 0.5, 0.0, 0.0, 1.0);
+        if down {
+            SdlEvent::FingerDown {
+                timestamp: 0,
+                touch_id: 1,
+                finger_id: 7,
+                x,
+                y,
+                dx,
+                dy,
+                pressure,
+            }
+        } else {
+            SdlEvent::FingerUp {
+                timestamp: 0,
+                touch_id: 1,
+                finger_id: 7,
+                x,
+                y,
+                dx,
+                dy,
+                pressure,
+            }
+        }
+    };
+    // A synthesized mouse lands a little off the touch, as rounding puts it.
+    let (x, y) = (SIDE as i32 / 2 + 3, SIDE as i32 / 2 + 3);
+    vec![
+        SdlEvent::MouseMotion {
+            timestamp: 0,
+            window_id: 1,
+            which: SDL_TOUCH_MOUSEID,
+            mousestate: MouseState::from_sdl_state(0),
+            x,
+            y,
+            xrel: 0,
+            yrel: 0,
+        },
+        SdlEvent::MouseButtonDown {
+            timestamp: 0,
+            window_id: 1,
+            which: SDL_TOUCH_MOUSEID,
+            mouse_btn: MouseButton::Left,
+            clicks: 1,
+            x,
+            y,
+        },
+        finger(true),
+        SdlEvent::MouseButtonUp {
+            timestamp: 0,
+            window_id: 1,
+            which: SDL_TOUCH_MOUSEID,
+            mouse_btn: MouseButton::Left,
+            clicks: 1,
+            x,
+            y,
+        },
+        finger(false),
+    ]
+}
+
+/// Where egui was told the primary button went down.
+fn presses(events: &[Event]) -> Vec<Pos2> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            Event::PointerButton {
+                pos,
+                button: PointerButton::Primary,
+                pressed: true,
+                ..
+            } => Some(*pos),
+            _ => None,
+        })
+        .collect()
+}
+
+// One test, as SDL is only to be brought up on one thread.
+#[test]
+fn a_tap_presses_once_unless_touch_mouse_events_are_asked_for() {
+    if std::env::var_os("SDL_VIDEODRIVER").is_none() {
+        sdl2::hint::set("SDL_VIDEODRIVER", "dummy");
+    }
+    let sdl = sdl2::init().expect("SDL");
+    let video = sdl.video().expect("SDL video");
+    let window = video
+        .window("touch mouse", SIDE, SIDE)
+        .hidden()
+        .build()
+        .expect("a window");
+    let mut state = State::new(&window, egui::Context::default(), egui::ViewportId::ROOT);
+    assert!(state.ignore_touch_mouse_events());
+
+    for event in tap() {
+        let _ = state.on_event(&window, &event);
+    }
+    let events = state.take_egui_input().events;
+    let centre = Pos2::new(SIDE as f32 / 2.0, SIDE as f32 / 2.0);
+    assert_eq!(presses(&events), vec![centre]);
+    assert!(
+        events
+            .iter()
+            .all(|event| !matches!(event, Event::PointerMoved(pos) if *pos != centre)),
+        "the pointer stays on the touch"
+    );
+
+    state.set_ignore_touch_mouse_events(false);
+    for event in tap() {
+        let _ = state.on_event(&window, &event);
+    }
+    let events = state.take_egui_input().events;
+    assert_eq!(presses(&events).len(), 2, "{events:?}");
+
+    // A device that scrolls leaves the pointer to the mouse events it makes.
+    state.set_ignore_touch_mouse_events(true);
+    state.set_touch_device_handling(1, Some(TouchHandling::Scroll));
+    for event in tap() {
+        let _ = state.on_event(&window, &event);
+    }
+    let events = state.take_egui_input().events;
+    let mouse = Pos2::new(centre.x + 3.0, centre.y + 3.0);
+    assert_eq!(presses(&events), vec![mouse], "{events:?}");
+    assert!(
+        events.iter().any(|event| matches!(
+            event,
+            Event::PointerButton {
+                button: PointerButton::Primary,
+                pressed: false,
+                ..
+            }
+        )),
+        "{events:?}"
+    );
+
+    // Touch handled elsewhere: the mouse events still arrive, by the frame.
+    state.set_touch_device_handling(1, None);
+    for event in tap().iter().filter(|event| !event.is_finger()) {
+        let _ = state.on_event(&window, event);
+    }
+    let events = state.take_egui_input().events;
+    assert_eq!(presses(&events), vec![mouse], "{events:?}");
+}